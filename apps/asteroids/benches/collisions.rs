@@ -0,0 +1,107 @@
+// Asteroid/bullet broadphase: the old nested loop against the spatial hash.
+// Run with `cargo bench`, it prints the average time per frame for growing
+// numbers of asteroids and bullets.
+use macroquad::prelude::*;
+use std::time::Instant;
+
+// The modules' own unit tests get compiled out here, which leaves their imports unused
+#[allow(dead_code, unused_imports)]
+#[path = "../src/geometry.rs"]
+mod geometry;
+#[allow(unused_imports)]
+#[path = "../src/spatial.rs"]
+mod spatial;
+
+use geometry::*;
+use spatial::*;
+
+const WORLD: Vec2 = Vec2::new(1920., 1080.);
+const FRAMES: u32 = 200;
+
+struct Body {
+    pos: Vec2,
+    radius: f32,
+}
+
+fn random_bodies(count: usize, radius: (f32, f32)) -> Vec<Body> {
+    (0..count)
+        .map(|_| Body {
+            pos: Vec2::new(rand::gen_range(0., WORLD.x), rand::gen_range(0., WORLD.y)),
+            radius: rand::gen_range(radius.0, radius.1),
+        })
+        .collect()
+}
+
+fn hit(asteroid: &Body, bullet: &Body) -> bool {
+    let delta = wrap_delta(asteroid.pos, bullet.pos, WORLD);
+    circles_overlap(Vec2::ZERO, asteroid.radius, delta, bullet.radius)
+}
+
+fn nested_loop(asteroids: &[Body], bullets: &[Body]) -> usize {
+    let mut hits = 0;
+    for bullet in bullets.iter() {
+        for asteroid in asteroids.iter() {
+            if hit(asteroid, bullet) {
+                hits += 1;
+            }
+        }
+    }
+    hits
+}
+
+fn spatial_hash(grid: &mut SpatialHash, asteroids: &[Body], bullets: &[Body]) -> usize {
+    grid.reset(WORLD, 64.);
+    for (i, asteroid) in asteroids.iter().enumerate() {
+        grid.insert(i, asteroid.pos, asteroid.radius);
+    }
+    let mut nearby = Vec::new();
+    let mut hits = 0;
+    for bullet in bullets.iter() {
+        grid.query(bullet.pos, bullet.radius, &mut nearby);
+        for &i in nearby.iter() {
+            if hit(&asteroids[i], bullet) {
+                hits += 1;
+            }
+        }
+    }
+    hits
+}
+
+fn time_per_frame(mut frame: impl FnMut() -> usize) -> (f64, usize) {
+    let start = Instant::now();
+    let mut hits = 0;
+    for _ in 0..FRAMES {
+        hits = std::hint::black_box(frame());
+    }
+    (start.elapsed().as_secs_f64() * 1e6 / FRAMES as f64, hits)
+}
+
+fn main() {
+    rand::srand(42);
+    let mut grid = SpatialHash::new(WORLD, 64.);
+
+    println!(
+        "{:>9} {:>8} {:>14} {:>14} {:>8}",
+        "asteroids", "bullets", "nested (us)", "hashed (us)", "speedup"
+    );
+    for &(asteroid_count, bullet_count) in
+        &[(50, 50), (100, 100), (250, 250), (500, 500), (1000, 1000)]
+    {
+        let asteroids = random_bodies(asteroid_count, (10., 60.));
+        let bullets = random_bodies(bullet_count, (0., 2.));
+
+        let (nested, nested_hits) = time_per_frame(|| nested_loop(&asteroids, &bullets));
+        let (hashed, hashed_hits) =
+            time_per_frame(|| spatial_hash(&mut grid, &asteroids, &bullets));
+        assert_eq!(nested_hits, hashed_hits, "broadphase missed a collision");
+
+        println!(
+            "{:>9} {:>8} {:>14.1} {:>14.1} {:>7.1}x",
+            asteroid_count,
+            bullet_count,
+            nested,
+            hashed,
+            nested / hashed
+        );
+    }
+}
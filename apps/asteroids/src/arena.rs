@@ -0,0 +1,111 @@
+use macroquad::prelude::*;
+
+use crate::screen_center;
+
+// Strength of the pull, acceleration in pixels per frame² is this over the distance squared
+const GRAVITY: f32 = 1000.;
+pub const STAR_RADIUS: f32 = 20.;
+// Orbiting rocks start between these distances from the star, as a share of the shorter screen side
+const ORBIT_RANGE: (f32, f32) = (0.15, 0.45);
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum Arena {
+    Classic,
+    // A black hole in the center pulls everything in
+    GravityWell,
+    // Rocks circle a star in the center, which pulls just as hard
+    Orbit,
+}
+
+impl Arena {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Arena::Classic => "Classic",
+            Arena::GravityWell => "Gravity well",
+            Arena::Orbit => "Orbit",
+        }
+    }
+
+    pub fn next(&self) -> Arena {
+        match self {
+            Arena::Classic => Arena::GravityWell,
+            Arena::GravityWell => Arena::Orbit,
+            Arena::Orbit => Arena::Classic,
+        }
+    }
+
+    pub fn has_star(&self) -> bool {
+        *self != Arena::Classic
+    }
+
+    /// Inverse-square pull towards the center, added to the velocity every frame.
+    pub fn gravity(&self, pos: Vec2) -> Vec2 {
+        if !self.has_star() {
+            return Vec2::ZERO;
+        }
+        let delta = screen_center() - pos;
+        // Nothing gets closer than the star's surface without being swallowed anyway
+        let distance = delta.length().max(STAR_RADIUS);
+        delta / distance * GRAVITY / (distance * distance)
+    }
+
+    /// Whether a circle touches the star and gets destroyed.
+    pub fn swallows(&self, pos: Vec2, radius: f32) -> bool {
+        self.has_star() && pos.distance(screen_center()) < STAR_RADIUS + radius
+    }
+
+    /// Velocity for a circular orbit through `pos`, counterclockwise.
+    pub fn orbit_velocity(&self, pos: Vec2) -> Vec2 {
+        let delta = pos - screen_center();
+        let distance = delta.length().max(STAR_RADIUS);
+        delta.perp().normalize_or_zero() * (GRAVITY / distance).sqrt()
+    }
+
+    /// Where a rock of a new wave may appear, the caller still keeps it away from the ships.
+    pub fn spawn_position(&self, size: f32) -> Vec2 {
+        match self {
+            Arena::Orbit => {
+                let side = screen_width().min(screen_height());
+                let distance = rand::gen_range(side * ORBIT_RANGE.0, side * ORBIT_RANGE.1);
+                screen_center()
+                    + Vec2::from_angle(rand::gen_range(0., std::f32::consts::TAU)) * distance
+            }
+            _ => {
+                let mut pos = Vec2::ZERO;
+                for _ in 0..100 {
+                    pos = Vec2::new(
+                        rand::gen_range(0., screen_width()),
+                        rand::gen_range(0., screen_height()),
+                    );
+                    if !self.swallows(pos, size * 3.) {
+                        break;
+                    }
+                }
+                pos
+            }
+        }
+    }
+
+    /// Velocity of a new rock, orbiting rocks get `vel` on top of their orbit.
+    pub fn launch_velocity(&self, pos: Vec2, vel: Vec2) -> Vec2 {
+        match self {
+            Arena::Orbit => self.orbit_velocity(pos) + vel * 0.3,
+            _ => vel,
+        }
+    }
+
+    pub fn draw(&self) {
+        let center = screen_center();
+        match self {
+            Arena::Classic => {}
+            Arena::GravityWell => {
+                draw_circle(center.x, center.y, STAR_RADIUS, BLACK);
+                draw_circle_lines(center.x, center.y, STAR_RADIUS * 1.6, 1., DARKGRAY);
+            }
+            Arena::Orbit => {
+                draw_circle(center.x, center.y, STAR_RADIUS, ORANGE);
+                draw_circle_lines(center.x, center.y, STAR_RADIUS, 2., BLACK);
+            }
+        }
+    }
+}
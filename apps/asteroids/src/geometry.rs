@@ -0,0 +1,316 @@
+use macroquad::prelude::*;
+
+// Polygons are plain vertex lists in draw order, the closing edge runs from the
+// last vertex back to the first. They do not have to be convex.
+
+/// Irregular rock outline around the origin. Every vertex gets its own radius,
+/// pulled in by up to `jitter` (0..1) of `radius`, so `radius` stays a bounding radius.
+/// The angles are nudged a little too but stay in order, the outline never crosses itself.
+pub fn jagged_polygon(vertices: usize, radius: f32, jitter: f32) -> Vec<Vec2> {
+    let step = std::f32::consts::TAU / vertices as f32;
+    (0..vertices)
+        .map(|i| {
+            let angle = (i as f32 + rand::gen_range(-0.3, 0.3)) * step;
+            let r = radius * (1. - rand::gen_range(0., jitter));
+            r * Vec2::from_angle(angle)
+        })
+        .collect()
+}
+
+/// Rotates (in degrees) and moves a shape defined around the origin into the world.
+pub fn transform_polygon(shape: &[Vec2], pos: Vec2, rotation: f32) -> Vec<Vec2> {
+    let rot = Vec2::from_angle(rotation.to_radians());
+    shape.iter().map(|v| pos + rot.rotate(*v)).collect()
+}
+
+pub fn translate_polygon(polygon: &[Vec2], offset: Vec2) -> Vec<Vec2> {
+    polygon.iter().map(|v| *v + offset).collect()
+}
+
+/// Shortest offset from `from` to `to` in a world of size `world` that wraps at
+/// its edges, so objects on opposite sides of a seam come out close together.
+pub fn wrap_delta(from: Vec2, to: Vec2, world: Vec2) -> Vec2 {
+    let mut delta = to - from;
+    if delta.x > world.x / 2. {
+        delta.x -= world.x;
+    } else if delta.x < -world.x / 2. {
+        delta.x += world.x;
+    }
+    if delta.y > world.y / 2. {
+        delta.y -= world.y;
+    } else if delta.y < -world.y / 2. {
+        delta.y += world.y;
+    }
+    delta
+}
+
+/// Ship triangle with the tip pointing along `rotation` (in degrees, 0 is up).
+pub fn ship_triangle(pos: Vec2, rotation: f32, height: f32, base: f32) -> [Vec2; 3] {
+    let rot = rotation.to_radians();
+    let forward = Vec2::new(rot.sin(), -rot.cos());
+    let side = Vec2::new(rot.cos(), rot.sin());
+    [
+        pos + forward * height / 2.,
+        pos - side * base / 2. - forward * height / 2.,
+        pos + side * base / 2. - forward * height / 2.,
+    ]
+}
+
+/// Radius of the smallest circle around `center` that holds every vertex.
+pub fn bounding_radius(center: Vec2, polygon: &[Vec2]) -> f32 {
+    polygon
+        .iter()
+        .map(|v| (*v - center).length())
+        .fold(0., f32::max)
+}
+
+/// Cheap early-out before the polygon tests, touching circles count as overlapping.
+pub fn circles_overlap(a: Vec2, a_radius: f32, b: Vec2, b_radius: f32) -> bool {
+    let radii = a_radius + b_radius;
+    (a - b).length_squared() <= radii * radii
+}
+
+// Sign of the turn from `a -> b` to `a -> c`, zero when the points are collinear
+fn orientation(a: Vec2, b: Vec2, c: Vec2) -> f32 {
+    (b - a).perp_dot(c - a)
+}
+
+// `p` lies within the bounding box of segment `a b`, only meaningful for collinear points
+fn on_segment(a: Vec2, b: Vec2, p: Vec2) -> bool {
+    p.x >= a.x.min(b.x) && p.x <= a.x.max(b.x) && p.y >= a.y.min(b.y) && p.y <= a.y.max(b.y)
+}
+
+/// Segments `p1 p2` and `q1 q2` cross or touch, including collinear overlap.
+pub fn segments_intersect(p1: Vec2, p2: Vec2, q1: Vec2, q2: Vec2) -> bool {
+    let d1 = orientation(q1, q2, p1);
+    let d2 = orientation(q1, q2, p2);
+    let d3 = orientation(p1, p2, q1);
+    let d4 = orientation(p1, p2, q2);
+
+    if ((d1 > 0. && d2 < 0.) || (d1 < 0. && d2 > 0.))
+        && ((d3 > 0. && d4 < 0.) || (d3 < 0. && d4 > 0.))
+    {
+        return true;
+    }
+
+    (d1 == 0. && on_segment(q1, q2, p1))
+        || (d2 == 0. && on_segment(q1, q2, p2))
+        || (d3 == 0. && on_segment(p1, p2, q1))
+        || (d4 == 0. && on_segment(p1, p2, q2))
+}
+
+/// Even-odd test, points exactly on an edge may fall either way.
+pub fn point_in_polygon(point: Vec2, polygon: &[Vec2]) -> bool {
+    let mut inside = false;
+    let mut j = polygon.len().wrapping_sub(1);
+    for i in 0..polygon.len() {
+        let (a, b) = (polygon[i], polygon[j]);
+        if (a.y > point.y) != (b.y > point.y)
+            && point.x < (b.x - a.x) * (point.y - a.y) / (b.y - a.y) + a.x
+        {
+            inside = !inside;
+        }
+        j = i;
+    }
+    inside
+}
+
+fn edges(polygon: &[Vec2]) -> impl Iterator<Item = (Vec2, Vec2)> + '_ {
+    polygon
+        .iter()
+        .zip(polygon.iter().cycle().skip(1))
+        .map(|(a, b)| (*a, *b))
+}
+
+/// Two polygons intersect when any of their edges cross, or when one lies
+/// completely inside the other.
+pub fn polygons_intersect(a: &[Vec2], b: &[Vec2]) -> bool {
+    if a.is_empty() || b.is_empty() {
+        return false;
+    }
+    for (a1, a2) in edges(a) {
+        for (b1, b2) in edges(b) {
+            if segments_intersect(a1, a2, b1, b2) {
+                return true;
+            }
+        }
+    }
+    point_in_polygon(a[0], b) || point_in_polygon(b[0], a)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn square(center: Vec2, half: f32) -> Vec<Vec2> {
+        vec![
+            center + Vec2::new(-half, -half),
+            center + Vec2::new(half, -half),
+            center + Vec2::new(half, half),
+            center + Vec2::new(-half, half),
+        ]
+    }
+
+    #[test]
+    fn jagged_polygon_stays_within_radius() {
+        for _ in 0..100 {
+            let shape = jagged_polygon(10, 20., 0.4);
+            assert_eq!(shape.len(), 10);
+            for v in shape.iter() {
+                assert!(v.length() <= 20. + 1e-4);
+                assert!(v.length() >= 12. - 1e-4);
+            }
+            // Angles only ever go up, so the outline is a simple polygon
+            let angles: Vec<f32> = shape
+                .iter()
+                .map(|v| v.y.atan2(v.x).rem_euclid(std::f32::consts::TAU))
+                .collect();
+            assert!(angles.windows(2).skip(1).all(|w| w[0] < w[1]));
+        }
+    }
+
+    #[test]
+    fn transform_polygon_rotates_then_moves() {
+        let shape = [Vec2::new(1., 0.)];
+        let moved = transform_polygon(&shape, Vec2::new(5., 5.), 90.);
+        assert!((moved[0] - Vec2::new(5., 6.)).length() < 1e-4);
+    }
+
+    #[test]
+    fn ship_triangle_points_along_rotation() {
+        let tri = ship_triangle(Vec2::ZERO, 0., 20., 10.);
+        assert!((tri[0] - Vec2::new(0., -10.)).length() < 1e-4);
+        let tri = ship_triangle(Vec2::ZERO, 90., 20., 10.);
+        assert!((tri[0] - Vec2::new(10., 0.)).length() < 1e-4);
+    }
+
+    #[test]
+    fn wrap_delta_takes_the_short_way() {
+        let world = Vec2::new(800., 600.);
+        let delta = wrap_delta(Vec2::new(790., 5.), Vec2::new(10., 595.), world);
+        assert!((delta - Vec2::new(20., -10.)).length() < 1e-4);
+        let delta = wrap_delta(Vec2::new(100., 100.), Vec2::new(200., 150.), world);
+        assert!((delta - Vec2::new(100., 50.)).length() < 1e-4);
+    }
+
+    #[test]
+    fn circles_touching_overlap() {
+        assert!(circles_overlap(Vec2::ZERO, 1., Vec2::new(2., 0.), 1.));
+        assert!(!circles_overlap(Vec2::ZERO, 1., Vec2::new(2.01, 0.), 1.));
+    }
+
+    #[test]
+    fn crossing_and_parallel_segments() {
+        let o = Vec2::ZERO;
+        assert!(segments_intersect(
+            o,
+            Vec2::new(2., 2.),
+            Vec2::new(0., 2.),
+            Vec2::new(2., 0.)
+        ));
+        assert!(!segments_intersect(
+            o,
+            Vec2::new(2., 0.),
+            Vec2::new(0., 1.),
+            Vec2::new(2., 1.)
+        ));
+    }
+
+    #[test]
+    fn touching_segments_intersect() {
+        // T junction and shared end point
+        assert!(segments_intersect(
+            Vec2::new(0., 0.),
+            Vec2::new(2., 0.),
+            Vec2::new(1., 0.),
+            Vec2::new(1., 1.)
+        ));
+        assert!(segments_intersect(
+            Vec2::new(0., 0.),
+            Vec2::new(1., 1.),
+            Vec2::new(1., 1.),
+            Vec2::new(2., 0.)
+        ));
+    }
+
+    #[test]
+    fn collinear_segments() {
+        let o = Vec2::ZERO;
+        assert!(segments_intersect(
+            o,
+            Vec2::new(2., 0.),
+            Vec2::new(1., 0.),
+            Vec2::new(3., 0.)
+        ));
+        assert!(!segments_intersect(
+            o,
+            Vec2::new(1., 0.),
+            Vec2::new(2., 0.),
+            Vec2::new(3., 0.)
+        ));
+    }
+
+    #[test]
+    fn point_inside_concave_polygon() {
+        // An arrow head with a notch cut into its bottom
+        let poly = vec![
+            Vec2::new(0., 0.),
+            Vec2::new(4., 4.),
+            Vec2::new(0., 2.),
+            Vec2::new(-4., 4.),
+        ];
+        assert!(point_in_polygon(Vec2::new(0., 1.), &poly));
+        assert!(!point_in_polygon(Vec2::new(0., 3.), &poly));
+        assert!(!point_in_polygon(Vec2::new(5., 0.), &poly));
+        assert!(!point_in_polygon(Vec2::ZERO, &[]));
+    }
+
+    #[test]
+    fn separated_polygons() {
+        let a = square(Vec2::ZERO, 1.);
+        let b = square(Vec2::new(3., 0.), 1.);
+        assert!(!polygons_intersect(&a, &b));
+    }
+
+    #[test]
+    fn bounding_circles_overlap_but_polygons_do_not() {
+        // Two triangles side by side, one upside down, with parallel facing edges
+        let a = ship_triangle(Vec2::ZERO, 0., 20., 20.);
+        let b = ship_triangle(Vec2::new(20., 0.), 180., 20., 20.);
+        assert!(circles_overlap(
+            Vec2::ZERO,
+            bounding_radius(Vec2::ZERO, &a),
+            Vec2::new(20., 0.),
+            bounding_radius(Vec2::new(20., 0.), &b),
+        ));
+        assert!(!polygons_intersect(&a, &b));
+    }
+
+    #[test]
+    fn overlapping_polygons() {
+        let a = square(Vec2::ZERO, 1.);
+        let b = square(Vec2::new(1.5, 1.5), 1.);
+        assert!(polygons_intersect(&a, &b));
+    }
+
+    #[test]
+    fn touching_polygons_intersect() {
+        let a = square(Vec2::ZERO, 1.);
+        let b = square(Vec2::new(2., 0.), 1.);
+        assert!(polygons_intersect(&a, &b));
+    }
+
+    #[test]
+    fn contained_polygon_intersects() {
+        // No edges cross when one shape is fully inside the other
+        let outer = square(Vec2::ZERO, 10.);
+        let inner = ship_triangle(Vec2::new(2., 3.), 45., 4., 4.);
+        assert!(polygons_intersect(&outer, &inner));
+        assert!(polygons_intersect(&inner, &outer));
+    }
+
+    #[test]
+    fn empty_polygon_never_intersects() {
+        assert!(!polygons_intersect(&[], &square(Vec2::ZERO, 1.)));
+    }
+}
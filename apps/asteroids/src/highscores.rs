@@ -0,0 +1,111 @@
+const STORAGE_KEY: &str = "asteroids_highscores";
+const MAX_ENTRIES: usize = 10;
+
+pub struct HighScore {
+    pub initials: String,
+    pub score: u32,
+}
+
+/// Top 10 table, best score first. Stored as one "AAA 12345" line per entry.
+pub struct HighScores {
+    pub entries: Vec<HighScore>,
+}
+
+impl HighScores {
+    pub fn load() -> Self {
+        Self::parse(&storage::load(STORAGE_KEY).unwrap_or_default())
+    }
+
+    pub fn save(&self) {
+        storage::save(STORAGE_KEY, &self.serialize());
+    }
+
+    // Lines that don't parse are skipped, a broken save file just loses those entries
+    fn parse(data: &str) -> Self {
+        let mut entries: Vec<HighScore> = data
+            .lines()
+            .filter_map(|line| {
+                let (initials, score) = line.trim().split_once(' ')?;
+                Some(HighScore {
+                    initials: initials.to_string(),
+                    score: score.trim().parse().ok()?,
+                })
+            })
+            .collect();
+        entries.sort_by_key(|entry| std::cmp::Reverse(entry.score));
+        entries.truncate(MAX_ENTRIES);
+        HighScores { entries }
+    }
+
+    fn serialize(&self) -> String {
+        self.entries
+            .iter()
+            .map(|entry| format!("{} {}\n", entry.initials, entry.score))
+            .collect()
+    }
+
+    pub fn qualifies(&self, score: u32) -> bool {
+        score > 0
+            && (self.entries.len() < MAX_ENTRIES
+                || self.entries.iter().any(|entry| score > entry.score))
+    }
+
+    // Ties go below the existing entries, whoever got there first keeps the spot
+    pub fn insert(&mut self, initials: &str, score: u32) {
+        let index = self
+            .entries
+            .iter()
+            .position(|entry| score > entry.score)
+            .unwrap_or(self.entries.len());
+        self.entries.insert(
+            index,
+            HighScore {
+                initials: initials.to_string(),
+                score,
+            },
+        );
+        self.entries.truncate(MAX_ENTRIES);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_skips_broken_lines_and_sorts() {
+        let scores = HighScores::parse("AAA 100\ngarbage\nBBB 300\nCCC x\n\nDDD 200\n");
+        let initials: Vec<&str> = scores.entries.iter().map(|e| e.initials.as_str()).collect();
+        assert_eq!(initials, ["BBB", "DDD", "AAA"]);
+    }
+
+    #[test]
+    fn serialize_round_trips() {
+        let mut scores = HighScores::parse("");
+        scores.insert("ABC", 1500);
+        scores.insert("XYZ", 2500);
+        let loaded = HighScores::parse(&scores.serialize());
+        assert_eq!(loaded.entries.len(), 2);
+        assert_eq!(loaded.entries[0].initials, "XYZ");
+        assert_eq!(loaded.entries[1].score, 1500);
+    }
+
+    #[test]
+    fn table_keeps_top_ten() {
+        let mut scores = HighScores::parse("");
+        for score in 1..=10 {
+            scores.insert("AAA", score * 100);
+        }
+        assert!(!scores.qualifies(100));
+        assert!(scores.qualifies(150));
+        scores.insert("NEW", 150);
+        assert_eq!(scores.entries.len(), 10);
+        assert_eq!(scores.entries[8].score, 200);
+        assert_eq!(scores.entries[9].initials, "NEW");
+    }
+
+    #[test]
+    fn zero_never_qualifies() {
+        assert!(!HighScores::parse("").qualifies(0));
+    }
+}
@@ -1,11 +1,75 @@
 use macroquad::prelude::*;
+mod arena;
+mod geometry;
+mod highscores;
+mod particles;
+mod player;
+mod powerups;
+mod spatial;
+mod ufo;
+
+use arena::*;
+use geometry::*;
+use highscores::*;
+use particles::*;
+use player::*;
+use powerups::*;
+use spatial::*;
+use ufo::*;
 
 const SHIP_HEIGHT: f32 = 25.;
 const SHIP_BASE: f32 = 22.;
+const WAVE_DELAY: f64 = 2.;
+const SAFE_SPAWN_DISTANCE: f32 = 150.;
+const MAX_WAVE_ASTEROIDS: u32 = 12;
+const MAX_ASTEROID_SPEED: f32 = 4.;
+const STARTING_LIVES: u32 = 3;
+// Two ships start side by side this far apart
+const SPAWN_SPACING: f32 = 80.;
+const RESPAWN_DELAY: f64 = 1.5;
+const INVULNERABLE_TIME: f64 = 2.5;
+const HYPERSPACE_COOLDOWN: f64 = 1.;
+const HYPERSPACE_FAILURE_CHANCE: f32 = 0.1;
+const UFO_MIN_DELAY: f64 = 10.;
+const UFO_MAX_DELAY: f64 = 20.;
+const EXTRA_LIFE_SCORE: u32 = 10000;
+// How far each rock vertex may be pulled in towards the center, as a share of its size
+const ASTEROID_JITTER: f32 = 0.35;
+const GRID_CELL_SIZE: f32 = 64.;
+// Fragments of a rock hit by a piercing shot can't be hit right away, or the
+// same bullet would shred the whole family in one go
+const PIERCE_IMMUNITY: f64 = 0.3;
+// Invulnerability after the shield soaked up a hit
+const SHIELD_BREAK_GRACE: f64 = 1.;
+
 struct Ship {
     pos: Vec2,
     rot: f32,
     vel: Vec2,
+    invulnerable_until: f64,
+}
+
+impl Ship {
+    fn new(pos: Vec2, invulnerable_until: f64) -> Self {
+        Ship {
+            pos,
+            rot: 0.,
+            vel: Vec2::new(0., 0.),
+            invulnerable_until,
+        }
+    }
+
+    fn is_invulnerable(&self, t: f64) -> bool {
+        t < self.invulnerable_until
+    }
+
+    fn polygon(&self) -> [Vec2; 3] {
+        ship_triangle(self.pos, self.rot, SHIP_HEIGHT, SHIP_BASE)
+    }
+
+    fn radius(&self) -> f32 {
+        bounding_radius(self.pos, &self.polygon())
+    }
 }
 
 struct Bullet {
@@ -13,6 +77,20 @@ struct Bullet {
     vel: Vec2,
     shot_at: f64,
     collided: bool,
+    // Index of the player who fired it, None for UFO shots
+    owner: Option<usize>,
+    // Keeps going after hitting something
+    piercing: bool,
+}
+
+impl Bullet {
+    // UFO shots hit every ship, player shots only the other ships and only with friendly fire on
+    fn hits_player(&self, player: usize, friendly_fire: bool) -> bool {
+        match self.owner {
+            None => true,
+            Some(owner) => friendly_fire && owner != player,
+        }
+    }
 }
 
 struct Asteroid {
@@ -21,8 +99,139 @@ struct Asteroid {
     rot: f32,
     rot_speed: f32,
     size: f32,
+    // Counts down with every split, rocks with 3 sides left don't break any further
     sides: u8,
+    // Outline around the center at zero rotation, `size` is its bounding radius
+    shape: Vec<Vec2>,
     collided: bool,
+    immune_until: f64,
+}
+
+impl Asteroid {
+    fn new(pos: Vec2, vel: Vec2, size: f32, sides: u8) -> Self {
+        Asteroid {
+            pos,
+            vel,
+            rot: rand::gen_range(0., 360.),
+            rot_speed: rand::gen_range(-2., 2.),
+            size,
+            sides,
+            // Bigger rocks get a more detailed outline
+            shape: jagged_polygon(sides as usize + 5, size, ASTEROID_JITTER),
+            collided: false,
+            immune_until: 0.,
+        }
+    }
+
+    fn polygon(&self) -> Vec<Vec2> {
+        transform_polygon(&self.shape, self.pos, self.rot)
+    }
+
+    // Small rocks are harder to hit and have fewer sides left to split off, so they pay more
+    fn points(&self) -> u32 {
+        let points = 20 + 10 * (8 - self.sides as u32) + (500. / self.size) as u32;
+        points / 5 * 5
+    }
+}
+
+enum GameMode {
+    Title,
+    Playing,
+    // Index of the player typing their initials
+    EnterInitials(usize),
+    GameOver,
+}
+
+// Picked on the title screen, kept when playing again
+#[derive(Clone, Copy)]
+struct Settings {
+    player_count: usize,
+    friendly_fire: bool,
+    arena: Arena,
+}
+
+struct GameState {
+    settings: Settings,
+    players: Vec<Player>,
+    bullets: Vec<Bullet>,
+    asteroids: Vec<Asteroid>,
+    ufos: Vec<Ufo>,
+    next_ufo_at: f64,
+    particles: ParticleSystem,
+    pickups: Vec<Pickup>,
+    // Asteroid broadphase, rebuilt every frame
+    grid: SpatialHash,
+    wave: u32,
+    // Set while waiting to spawn the next wave, the "Wave N" banner is shown until then
+    next_wave_at: Option<f64>,
+}
+
+impl GameState {
+    fn new(settings: Settings) -> Self {
+        let players = [
+            ("P1", BLACK, PLAYER_ONE_CONTROLS),
+            ("P2", DARKBLUE, PLAYER_TWO_CONTROLS),
+        ]
+        .into_iter()
+        .take(settings.player_count)
+        .enumerate()
+        .map(|(i, (name, color, controls))| {
+            Player::new(name, color, controls, spawn_ship(i, &settings, 0.))
+        })
+        .collect();
+        Self {
+            settings,
+            players,
+            bullets: Vec::new(),
+            asteroids: Vec::new(),
+            ufos: Vec::new(),
+            next_ufo_at: get_time() + rand::gen_range(UFO_MIN_DELAY, UFO_MAX_DELAY),
+            particles: ParticleSystem::new(),
+            pickups: Vec::new(),
+            grid: SpatialHash::new(world_size(), GRID_CELL_SIZE),
+            wave: 0,
+            next_wave_at: None,
+        }
+    }
+}
+
+fn screen_center() -> Vec2 {
+    Vec2::new(screen_width() / 2., screen_height() / 2.)
+}
+
+// Where a player's ship starts and respawns, side by side around the center or
+// above the star if there is one
+fn spawn_point(index: usize, settings: &Settings) -> Vec2 {
+    let offset = index as f32 - (settings.player_count - 1) as f32 / 2.;
+    let height = if settings.arena.has_star() {
+        -screen_width().min(screen_height()) / 3.
+    } else {
+        0.
+    };
+    screen_center() + Vec2::new(offset * SPAWN_SPACING, height)
+}
+
+// Around a star ships start out in orbit, otherwise they would drop right in
+fn spawn_ship(index: usize, settings: &Settings, invulnerable_until: f64) -> Ship {
+    let mut ship = Ship::new(spawn_point(index, settings), invulnerable_until);
+    if settings.arena.has_star() {
+        ship.vel = settings.arena.orbit_velocity(ship.pos);
+    }
+    ship
+}
+
+fn world_size() -> Vec2 {
+    Vec2::new(screen_width(), screen_height())
+}
+
+// Offset from `from` to `to` going the short way, possibly across a screen edge
+fn seam_delta(from: Vec2, to: Vec2) -> Vec2 {
+    wrap_delta(from, to, world_size())
+}
+
+// Where `pos` shows up when looking from `from`, used to test collisions across the seam
+fn nearest_image(from: Vec2, pos: Vec2) -> Vec2 {
+    from + seam_delta(from, pos)
 }
 
 fn wrap_around(v: &Vec2) -> Vec2 {
@@ -42,207 +251,682 @@ fn wrap_around(v: &Vec2) -> Vec2 {
     vr
 }
 
+// Every wave adds one asteroid and makes them all a bit faster
+fn spawn_wave(wave: u32, arena: Arena, avoid: &[Vec2]) -> Vec<Asteroid> {
+    let count = (3 + wave).min(MAX_WAVE_ASTEROIDS);
+    let speed = (1. + 0.25 * (wave - 1) as f32).min(MAX_ASTEROID_SPEED);
+    let size = screen_width().min(screen_height()) / 10.;
+
+    (0..count)
+        .map(|_| {
+            // Keep rolling a position until it is far enough from every ship
+            let mut pos = screen_center();
+            for _ in 0..100 {
+                pos = arena.spawn_position(size);
+                if avoid.iter().all(|&ship_pos| {
+                    seam_delta(ship_pos, pos).length() > SAFE_SPAWN_DISTANCE + size
+                }) {
+                    break;
+                }
+            }
+            let vel = Vec2::from_angle(rand::gen_range(0., std::f32::consts::TAU))
+                * speed
+                * rand::gen_range(0.5, 1.);
+            Asteroid::new(
+                pos,
+                arena.launch_velocity(pos, vel),
+                size,
+                rand::gen_range(3, 8),
+            )
+        })
+        .collect()
+}
+
+// Nothing may come near the spawn point while a ship is waiting to respawn there
+fn is_respawn_safe(asteroids: &[Asteroid], spawn: Vec2) -> bool {
+    asteroids.iter().all(|asteroid| {
+        seam_delta(spawn, asteroid.pos).length() > SAFE_SPAWN_DISTANCE + asteroid.size
+    })
+}
+
+// Lets every player from `from` on whose score made the table type their initials, in turn
+fn enter_initials_or_game_over(
+    highscores: &HighScores,
+    players: &[Player],
+    from: usize,
+) -> GameMode {
+    match (from..players.len()).find(|&i| highscores.qualifies(players[i].score)) {
+        Some(i) => GameMode::EnterInitials(i),
+        None => GameMode::GameOver,
+    }
+}
+
+fn draw_polygon_lines(polygon: &[Vec2]) {
+    for (i, v) in polygon.iter().enumerate() {
+        let next = polygon[(i + 1) % polygon.len()];
+        draw_line(v.x, v.y, next.x, next.y, 2., BLACK);
+    }
+}
+
+fn draw_ship(ship: &Ship, color: Color) {
+    let [v1, v2, v3] = ship.polygon();
+    draw_triangle_lines(v1, v2, v3, 2., color);
+}
+
+fn draw_centered_text(text: &str, y: f32, font_size: f32) {
+    let text_size = measure_text(text, None, font_size as _, 1.0);
+    draw_text(
+        text,
+        screen_width() / 2. - text_size.width / 2.,
+        y - text_size.height / 2.,
+        font_size,
+        DARKGRAY,
+    );
+}
+
+fn draw_highscores(highscores: &HighScores, y: f32) {
+    draw_centered_text("HIGH SCORES", y, 30.);
+    for (i, entry) in highscores.entries.iter().enumerate() {
+        draw_centered_text(
+            &format!("{:>2}. {}  {:>7}", i + 1, entry.initials, entry.score),
+            y + 35. + i as f32 * 26.,
+            26.,
+        );
+    }
+}
+
 #[macroquad::main("Asteroids")]
 async fn main() {
-    let mut ship = Ship {
-        pos: Vec2::new(screen_width() / 2., screen_height() / 2.),
-        rot: 0.,
-        vel: Vec2::new(0., 0.),
+    let mut settings = Settings {
+        player_count: 1,
+        friendly_fire: false,
+        arena: Arena::Classic,
     };
-
-    let mut bullets = Vec::new();
-    let mut last_shot = get_time();
-    let mut asteroids = Vec::new();
-    let mut gameover = false;
-
-    let mut screen_center;
+    let mut game = GameState::new(settings);
+    let mut mode = GameMode::Title;
+    let mut highscores = HighScores::load();
+    let mut initials = String::new();
 
     loop {
-        if gameover {
-            clear_background(LIGHTGRAY);
-            let mut text = "You Win!. Press [enter] to play again.";
-            let font_size = 30.;
-
-            if asteroids.len() > 0 {
-                text = "Game Over. Press [enter] to play again.";
+        match mode {
+            GameMode::Title => {
+                clear_background(LIGHTGRAY);
+                draw_centered_text("ASTEROIDS", screen_height() * 0.15, 60.);
+                draw_centered_text(
+                    "Arrows to steer and thrust, [space] to shoot, [down] for hyperspace",
+                    screen_height() * 0.15 + 50.,
+                    24.,
+                );
+                draw_centered_text(
+                    "Player 2: WASD to steer and thrust, [left shift] to shoot, [s] for hyperspace",
+                    screen_height() * 0.15 + 75.,
+                    24.,
+                );
+                draw_centered_text(
+                    "Press [enter] to start, [2] for two players",
+                    screen_height() * 0.15 + 115.,
+                    30.,
+                );
+                draw_centered_text(
+                    &format!(
+                        "Friendly fire {}, [f] to toggle",
+                        if settings.friendly_fire { "on" } else { "off" }
+                    ),
+                    screen_height() * 0.15 + 145.,
+                    24.,
+                );
+                draw_centered_text(
+                    &format!("Arena: {}, [m] to change", settings.arena.name()),
+                    screen_height() * 0.15 + 170.,
+                    24.,
+                );
+                draw_highscores(&highscores, screen_height() * 0.15 + 235.);
+                if is_key_pressed(KeyCode::F) {
+                    settings.friendly_fire = !settings.friendly_fire;
+                }
+                if is_key_pressed(KeyCode::M) {
+                    settings.arena = settings.arena.next();
+                }
+                if is_key_pressed(KeyCode::Enter) {
+                    settings.player_count = 1;
+                    game = GameState::new(settings);
+                    mode = GameMode::Playing;
+                } else if is_key_pressed(KeyCode::Key2) {
+                    settings.player_count = 2;
+                    game = GameState::new(settings);
+                    mode = GameMode::Playing;
+                }
+                next_frame().await;
+                continue;
             }
-            let text_size = measure_text(text, None, font_size as _, 1.0);
-            draw_text(
-                text,
-                screen_width() / 2. - text_size.width / 2.,
-                screen_height() / 2. - text_size.height / 2.,
-                font_size,
-                DARKGRAY,
-            );
-            if is_key_down(KeyCode::Enter) {
-                ship = Ship {
-                    pos: Vec2::new(screen_width() / 2., screen_height() / 2.),
-                    rot: 0.,
-                    vel: Vec2::new(0., 0.),
+            GameMode::EnterInitials(index) => {
+                let player = &game.players[index];
+
+                // Three letters, backspace to correct, enter to confirm
+                while let Some(c) = get_char_pressed() {
+                    if c.is_ascii_alphabetic() && initials.len() < 3 {
+                        initials.push(c.to_ascii_uppercase());
+                    }
+                }
+                if is_key_pressed(KeyCode::Backspace) {
+                    initials.pop();
+                }
+                if is_key_pressed(KeyCode::Enter) && initials.len() == 3 {
+                    highscores.insert(&initials, player.score);
+                    highscores.save();
+                    initials.clear();
+                    mode = enter_initials_or_game_over(&highscores, &game.players, index + 1);
+                }
+
+                clear_background(LIGHTGRAY);
+                let title = if game.players.len() > 1 {
+                    format!("New high score for {}: {}", player.name, player.score)
+                } else {
+                    format!("New high score: {}", player.score)
                 };
-                bullets = Vec::new();
-                asteroids = Vec::new();
-                gameover = false;
-                screen_center = Vec2::new(screen_width() / 2., screen_height() / 2.);
-                for _ in 0..10 {
-                    asteroids.push(Asteroid {
-                        pos: screen_center
-                            + Vec2::new(rand::gen_range(-1., 1.), rand::gen_range(-1., 1.))
-                                .normalize()
-                                * screen_width().min(screen_height())
-                                / 2.,
-                        vel: Vec2::new(rand::gen_range(-1., 1.), rand::gen_range(-1., 1.)),
-                        rot: 0.,
-                        rot_speed: rand::gen_range(-2., 2.),
-                        size: screen_width().min(screen_height()) / 10.,
-                        sides: rand::gen_range(3, 8),
-                        collided: false,
+                draw_centered_text(&title, screen_height() / 2. - 40., 40.);
+                draw_centered_text(
+                    &format!("Enter your initials: {:_<3}", initials),
+                    screen_height() / 2. + 10.,
+                    30.,
+                );
+                draw_centered_text("Press [enter] to confirm", screen_height() / 2. + 50., 24.);
+                next_frame().await;
+                continue;
+            }
+            GameMode::GameOver => {
+                clear_background(LIGHTGRAY);
+                let scores: Vec<String> = game
+                    .players
+                    .iter()
+                    .map(|player| {
+                        if game.players.len() > 1 {
+                            format!("{} {} points", player.name, player.score)
+                        } else {
+                            format!("{} points", player.score)
+                        }
                     })
+                    .collect();
+                draw_centered_text(
+                    &format!("Game Over on wave {} with {}", game.wave, scores.join(", ")),
+                    screen_height() * 0.15,
+                    30.,
+                );
+                draw_centered_text(
+                    "Press [enter] to play again, [escape] for the title screen.",
+                    screen_height() * 0.15 + 40.,
+                    30.,
+                );
+                draw_highscores(&highscores, screen_height() * 0.15 + 110.);
+                if is_key_pressed(KeyCode::Enter) {
+                    game = GameState::new(game.settings);
+                    mode = GameMode::Playing;
+                } else if is_key_pressed(KeyCode::Escape) {
+                    mode = GameMode::Title;
                 }
+                next_frame().await;
+                continue;
             }
-            next_frame().await;
-            continue;
+            GameMode::Playing => {}
         }
-        let frame_t = get_time();
-        let rotation = ship.rot.to_radians();
 
-        let mut acc = -ship.vel / 100.; // Friction
+        let frame_t = get_time();
+        let player_count = game.players.len();
+        let arena = game.settings.arena;
 
-        // Forward
-        if is_key_down(KeyCode::Up) {
-            acc = Vec2::new(rotation.sin(), -rotation.cos()) / 3.;
+        // Respawn once no asteroid is close to the spawn point
+        for (index, player) in game.players.iter_mut().enumerate() {
+            let spawn = spawn_point(index, &game.settings);
+            if player
+                .respawn_at
+                .is_some_and(|respawn_at| frame_t >= respawn_at)
+                && player.lives > 0
+                && is_respawn_safe(&game.asteroids, spawn)
+            {
+                player.ship = spawn_ship(index, &game.settings, frame_t + INVULNERABLE_TIME);
+                player.respawn_at = None;
+            }
         }
 
-        // Shot
-        if is_key_down(KeyCode::Space) && frame_t - last_shot > 0.5 {
-            let rot_vec = Vec2::new(rotation.sin(), -rotation.cos());
-            bullets.push(Bullet {
-                pos: ship.pos + rot_vec * SHIP_HEIGHT / 2.,
-                vel: rot_vec * 7.,
-                shot_at: frame_t,
-                collided: false,
-            });
-            last_shot = frame_t;
+        // End the game once every player is out and the last explosion played out
+        if game.players.iter().all(|player| player.is_out(frame_t)) {
+            // Drop whatever was typed while playing
+            while get_char_pressed().is_some() {}
+            initials.clear();
+            mode = enter_initials_or_game_over(&highscores, &game.players, 0);
+            continue;
         }
 
-        // Steer
-        if is_key_down(KeyCode::Right) {
-            ship.rot += 5.;
-        } else if is_key_down(KeyCode::Left) {
-            ship.rot -= 5.;
-        }
+        let players = &mut game.players;
+        let bullets = &mut game.bullets;
+        let asteroids = &mut game.asteroids;
+        let ufos = &mut game.ufos;
+        let particles = &mut game.particles;
+        let pickups = &mut game.pickups;
+
+        for (index, player) in players.iter_mut().enumerate() {
+            player.power_ups.update(frame_t);
+            if !player.is_alive() {
+                continue;
+            }
 
-        // Euler integration
-        ship.vel += acc;
-        if ship.vel.length() > 5. {
-            ship.vel = ship.vel.normalize() * 5.;
+            let loadout = player.power_ups.loadout();
+            let controls = &player.controls;
+            let ship = &mut player.ship;
+            let rotation = ship.rot.to_radians();
+
+            let mut acc = -ship.vel / 100.; // Friction
+
+            // Forward
+            if is_key_down(controls.thrust) {
+                let forward = Vec2::new(rotation.sin(), -rotation.cos());
+                acc = forward / 3.;
+                // Exhaust out of the back of the ship
+                particles.emit(
+                    &EXHAUST,
+                    ship.pos - forward * SHIP_HEIGHT / 2.,
+                    (-forward).y.atan2((-forward).x),
+                    ship.vel,
+                );
+            }
+
+            // Shot
+            if is_key_down(controls.shoot) && frame_t - player.last_shot > loadout.reload {
+                // A volley fans out evenly around the nose
+                for i in 0..loadout.shots {
+                    let offset = (i as f32 - (loadout.shots - 1) as f32 / 2.) * loadout.spread;
+                    let angle = rotation + offset;
+                    let rot_vec = Vec2::new(angle.sin(), -angle.cos());
+                    bullets.push(Bullet {
+                        pos: ship.pos + rot_vec * SHIP_HEIGHT / 2.,
+                        vel: rot_vec * 7.,
+                        shot_at: frame_t,
+                        collided: false,
+                        owner: Some(index),
+                        piercing: loadout.piercing,
+                    });
+                }
+                player.last_shot = frame_t;
+            }
+
+            // Steer
+            if is_key_down(controls.right) {
+                ship.rot += 5.;
+            } else if is_key_down(controls.left) {
+                ship.rot -= 5.;
+            }
+
+            // Hyperspace, jump to a random spot with a small chance of blowing up
+            if is_key_pressed(controls.hyperspace)
+                && frame_t - player.last_hyperspace > HYPERSPACE_COOLDOWN
+            {
+                ship.pos = Vec2::new(
+                    rand::gen_range(0., screen_width()),
+                    rand::gen_range(0., screen_height()),
+                );
+                ship.vel = Vec2::new(0., 0.);
+                player.last_hyperspace = frame_t;
+                if rand::gen_range(0., 1.) < HYPERSPACE_FAILURE_CHANCE {
+                    player.destroy(frame_t, particles);
+                    continue;
+                }
+            }
+
+            // Euler integration
+            ship.vel += acc + arena.gravity(ship.pos);
+            if ship.vel.length() > 5. {
+                ship.vel = ship.vel.normalize() * 5.;
+            }
+            ship.pos += ship.vel;
+            ship.pos = wrap_around(&ship.pos);
         }
-        ship.pos += ship.vel;
-        ship.pos = wrap_around(&ship.pos);
 
         // Move each bullet
         for bullet in bullets.iter_mut() {
+            bullet.vel += arena.gravity(bullet.pos);
             bullet.pos += bullet.vel;
+            bullet.pos = wrap_around(&bullet.pos);
         }
 
         // Move each asteroid
         for asteroid in asteroids.iter_mut() {
+            asteroid.vel += arena.gravity(asteroid.pos);
             asteroid.pos += asteroid.vel;
             asteroid.pos = wrap_around(&asteroid.pos);
             asteroid.rot += asteroid.rot_speed;
         }
 
+        // UFOs only show up while there are asteroids left, the small ones get likelier every wave
+        if !asteroids.is_empty() && ufos.is_empty() && frame_t >= game.next_ufo_at {
+            let small_chance = (0.1 + 0.1 * game.wave as f32).min(0.8);
+            let kind = if rand::gen_range(0., 1.) < small_chance {
+                UfoKind::Small
+            } else {
+                UfoKind::Large
+            };
+            ufos.push(Ufo::spawn(kind, frame_t));
+            game.next_ufo_at = frame_t + rand::gen_range(UFO_MIN_DELAY, UFO_MAX_DELAY);
+        }
+
+        // Move each UFO and let it shoot at the closest ship
+        ufos.retain_mut(|ufo| ufo.update(frame_t));
+        for ufo in ufos.iter_mut() {
+            let target = players
+                .iter()
+                .filter(|player| player.is_alive())
+                .map(|player| player.ship.pos)
+                .min_by(|a, b| {
+                    let a = seam_delta(ufo.pos, *a).length();
+                    let b = seam_delta(ufo.pos, *b).length();
+                    a.total_cmp(&b)
+                });
+            if let Some(bullet) = ufo.shoot(frame_t, target, game.wave) {
+                bullets.push(bullet);
+            }
+        }
+
+        // Whatever touches the star is gone, shields and invulnerability don't help
+        for player in players.iter_mut().filter(|player| player.is_alive()) {
+            if arena.swallows(player.ship.pos, player.ship.radius()) {
+                player.destroy(frame_t, particles);
+            }
+        }
+        bullets.retain(|bullet| !arena.swallows(bullet.pos, 0.));
+        asteroids.retain(|asteroid| {
+            let swallowed = arena.swallows(asteroid.pos, asteroid.size);
+            if swallowed {
+                particles.emit(&DEBRIS, asteroid.pos, 0., asteroid.vel);
+            }
+            !swallowed
+        });
+        for ufo in ufos.iter_mut() {
+            if arena.swallows(ufo.pos, ufo.size()) {
+                ufo.collided = true;
+            }
+        }
+
         // Bullet lifetime
         bullets.retain(|bullet| bullet.shot_at + 1.5 > frame_t);
 
-        let mut new_asteroids = Vec::new();
-        for asteroid in asteroids.iter_mut() {
-            // Asteroid/ship collision
-            if (asteroid.pos - ship.pos).length() < asteroid.size + SHIP_HEIGHT / 3. {
-                gameover = true;
-                break;
+        // Broadphase: bucket the asteroids so everything else only looks at the ones nearby
+        let grid = &mut game.grid;
+        grid.reset(world_size(), GRID_CELL_SIZE);
+        for (i, asteroid) in asteroids.iter().enumerate() {
+            grid.insert(i, asteroid.pos, asteroid.size);
+        }
+        let mut nearby = Vec::new();
+
+        // Ships that can be hit this frame
+        let targets: Vec<usize> = (0..players.len())
+            .filter(|&i| players[i].is_alive() && !players[i].ship.is_invulnerable(frame_t))
+            .collect();
+        let mut ship_hits = vec![false; players.len()];
+
+        // Asteroid/ship collision
+        for &index in targets.iter() {
+            let ship = &players[index].ship;
+            let ship_polygon = ship.polygon();
+            grid.query(ship.pos, ship.radius(), &mut nearby);
+            for &i in nearby.iter() {
+                let asteroid = &asteroids[i];
+                let ship_image = nearest_image(asteroid.pos, ship.pos);
+                if circles_overlap(asteroid.pos, asteroid.size, ship_image, ship.radius())
+                    && polygons_intersect(
+                        &asteroid.polygon(),
+                        &translate_polygon(&ship_polygon, ship_image - ship.pos),
+                    )
+                {
+                    ship_hits[index] = true;
+                    break;
+                }
             }
+        }
 
-            // Asteroid/bullet collision
-            for bullet in bullets.iter_mut() {
-                if (asteroid.pos - bullet.pos).length() < asteroid.size {
+        // Asteroid/bullet collision
+        let mut new_asteroids = Vec::new();
+        for bullet in bullets.iter_mut() {
+            grid.query(bullet.pos, 0., &mut nearby);
+            for &i in nearby.iter() {
+                let asteroid = &mut asteroids[i];
+                if asteroid.collided || frame_t < asteroid.immune_until {
+                    continue;
+                }
+                let bullet_pos = nearest_image(asteroid.pos, bullet.pos);
+                if circles_overlap(asteroid.pos, asteroid.size, bullet_pos, 0.)
+                    && point_in_polygon(bullet_pos, &asteroid.polygon())
+                {
                     asteroid.collided = true;
-                    bullet.collided = true;
+                    bullet.collided = !bullet.piercing;
+                    if let Some(owner) = bullet.owner {
+                        players[owner].add_score(asteroid.points());
+                    }
 
                     // Break the asteroid
                     if asteroid.sides > 3 {
-                        new_asteroids.push(Asteroid {
-                            pos: asteroid.pos,
-                            vel: Vec2::new(bullet.vel.y, -bullet.vel.x).normalize()
-                                * rand::gen_range(1., 3.),
-                            rot: rand::gen_range(0., 360.),
-                            rot_speed: rand::gen_range(-2., 2.),
-                            size: asteroid.size * 0.8,
-                            sides: asteroid.sides - 1,
-                            collided: false,
-                        });
-                        new_asteroids.push(Asteroid {
-                            pos: asteroid.pos,
-                            vel: Vec2::new(-bullet.vel.y, bullet.vel.x).normalize()
-                                * rand::gen_range(1., 3.),
-                            rot: rand::gen_range(0., 360.),
-                            rot_speed: rand::gen_range(-2., 2.),
-                            size: asteroid.size * 0.8,
-                            sides: asteroid.sides - 1,
-                            collided: false,
-                        })
+                        for dir in [
+                            Vec2::new(bullet.vel.y, -bullet.vel.x),
+                            Vec2::new(-bullet.vel.y, bullet.vel.x),
+                        ] {
+                            let mut fragment = Asteroid::new(
+                                asteroid.pos,
+                                arena.launch_velocity(
+                                    asteroid.pos,
+                                    dir.normalize() * rand::gen_range(1., 3.),
+                                ),
+                                asteroid.size * 0.8,
+                                asteroid.sides - 1,
+                            );
+                            if bullet.piercing {
+                                fragment.immune_until = frame_t + PIERCE_IMMUNITY;
+                            }
+                            new_asteroids.push(fragment);
+                        }
                     }
                     break;
                 }
             }
         }
 
+        for ufo in ufos.iter_mut() {
+            let polygon = ufo.polygon();
+
+            // UFO/player bullet collision
+            for bullet in bullets.iter_mut() {
+                let Some(owner) = bullet.owner else {
+                    continue;
+                };
+                let bullet_pos = nearest_image(ufo.pos, bullet.pos);
+                if !bullet.collided
+                    && circles_overlap(ufo.pos, ufo.size(), bullet_pos, 0.)
+                    && point_in_polygon(bullet_pos, &polygon)
+                {
+                    ufo.collided = true;
+                    bullet.collided = !bullet.piercing;
+                    players[owner].add_score(ufo.points());
+                    break;
+                }
+            }
+
+            // UFO/ship collision
+            for &index in targets.iter() {
+                let ship = &players[index].ship;
+                let ship_image = nearest_image(ufo.pos, ship.pos);
+                if circles_overlap(ufo.pos, ufo.size(), ship_image, ship.radius())
+                    && polygons_intersect(
+                        &polygon,
+                        &translate_polygon(&ship.polygon(), ship_image - ship.pos),
+                    )
+                {
+                    ufo.collided = true;
+                    ship_hits[index] = true;
+                }
+            }
+
+            // UFO/asteroid collision, the UFO does not survive
+            grid.query(ufo.pos, ufo.size(), &mut nearby);
+            if nearby.iter().any(|&i| {
+                let asteroid = &asteroids[i];
+                let ufo_image = nearest_image(asteroid.pos, ufo.pos);
+                circles_overlap(ufo_image, ufo.size(), asteroid.pos, asteroid.size)
+                    && polygons_intersect(
+                        &translate_polygon(&polygon, ufo_image - ufo.pos),
+                        &asteroid.polygon(),
+                    )
+            }) {
+                ufo.collided = true;
+            }
+        }
+
+        // Bullet/ship collision, UFO shots and with friendly fire the other player's
+        for &index in targets.iter() {
+            let ship = &players[index].ship;
+            let ship_polygon = ship.polygon();
+            for bullet in bullets.iter_mut() {
+                let bullet_pos = nearest_image(ship.pos, bullet.pos);
+                if bullet.hits_player(index, game.settings.friendly_fire)
+                    && !bullet.collided
+                    && circles_overlap(ship.pos, ship.radius(), bullet_pos, 0.)
+                    && point_in_polygon(bullet_pos, &ship_polygon)
+                {
+                    bullet.collided = true;
+                    ship_hits[index] = true;
+                }
+            }
+        }
+
+        // The shield takes the hit instead of the ship
+        for (player, hit) in players.iter_mut().zip(ship_hits) {
+            if !hit {
+                continue;
+            }
+            if player.power_ups.absorb_hit() {
+                player.ship.invulnerable_until = frame_t + SHIELD_BREAK_GRACE;
+            } else {
+                player.destroy(frame_t, particles);
+            }
+        }
+
+        // Power-up pickups, first come first served
+        pickups.retain_mut(|pickup| pickup.update(frame_t));
+        for player in players.iter_mut().filter(|player| player.is_alive()) {
+            let ship = &player.ship;
+            pickups.retain(|pickup| {
+                let picked = circles_overlap(
+                    ship.pos,
+                    ship.radius(),
+                    nearest_image(ship.pos, pickup.pos),
+                    PICKUP_RADIUS,
+                );
+                if picked {
+                    player.power_ups.collect(pickup.power_up, frame_t);
+                }
+                !picked
+            });
+        }
+
+        // Debris and explosions for everything that got destroyed
+        for asteroid in asteroids.iter().filter(|asteroid| asteroid.collided) {
+            particles.emit(&DEBRIS, asteroid.pos, 0., asteroid.vel);
+            if let Some(pickup) = Pickup::roll(asteroid.pos, frame_t) {
+                pickups.push(pickup);
+            }
+        }
+        for ufo in ufos.iter().filter(|ufo| ufo.collided) {
+            particles.emit(&SHIP_EXPLOSION, ufo.pos, 0., ufo.vel);
+        }
+
         // Remove the collided objects
         bullets.retain(|bullet| bullet.shot_at + 1.5 > frame_t && !bullet.collided);
         asteroids.retain(|asteroid| !asteroid.collided);
         asteroids.append(&mut new_asteroids);
+        ufos.retain(|ufo| !ufo.collided);
 
-        // You win?
-        if asteroids.len() == 0 {
-            gameover = true;
+        // Wave cleared, queue up the next one
+        if asteroids.is_empty() && game.next_wave_at.is_none() {
+            game.wave += 1;
+            game.next_wave_at = Some(frame_t + WAVE_DELAY);
         }
-
-        if gameover {
-            continue;
+        if let Some(next_wave_at) = game.next_wave_at {
+            if frame_t >= next_wave_at {
+                let avoid: Vec<Vec2> = players
+                    .iter()
+                    .enumerate()
+                    .map(|(index, player)| {
+                        if player.is_alive() {
+                            player.ship.pos
+                        } else {
+                            spawn_point(index, &game.settings)
+                        }
+                    })
+                    .collect();
+                *asteroids = spawn_wave(game.wave, arena, &avoid);
+                game.next_wave_at = None;
+            }
         }
 
+        particles.update(get_frame_time());
+
         clear_background(LIGHTGRAY);
 
+        arena.draw();
+        particles.draw();
+
         for bullet in bullets.iter() {
             draw_circle(bullet.pos.x, bullet.pos.y, 2., BLACK);
         }
 
         for asteroid in asteroids.iter() {
-            draw_poly_lines(
-                asteroid.pos.x,
-                asteroid.pos.y,
-                asteroid.sides,
-                asteroid.size,
-                asteroid.rot,
-                2.,
-                BLACK,
-            )
+            draw_polygon_lines(&asteroid.polygon());
         }
 
-        let v1 = Vec2::new(
-            ship.pos.x + rotation.sin() * SHIP_HEIGHT / 2.,
-            ship.pos.y - rotation.cos() * SHIP_HEIGHT / 2.,
-        );
-        let v2 = Vec2::new(
-            ship.pos.x - rotation.cos() * SHIP_BASE / 2. - rotation.sin() * SHIP_HEIGHT / 2.,
-            ship.pos.y - rotation.sin() * SHIP_BASE / 2. + rotation.cos() * SHIP_HEIGHT / 2.,
-        );
-        let v3 = Vec2::new(
-            ship.pos.x + rotation.cos() * SHIP_BASE / 2. - rotation.sin() * SHIP_HEIGHT / 2.,
-            ship.pos.y + rotation.sin() * SHIP_BASE / 2. + rotation.cos() * SHIP_HEIGHT / 2.,
-        );
-        draw_triangle_lines(v1, v2, v3, 2., BLACK);
+        for ufo in ufos.iter() {
+            draw_ufo(ufo);
+        }
+
+        for pickup in pickups.iter() {
+            pickup.draw(frame_t);
+        }
+
+        for player in players.iter().filter(|player| player.is_alive()) {
+            let ship = &player.ship;
+            if player.power_ups.loadout().shield {
+                draw_circle_lines(ship.pos.x, ship.pos.y, ship.radius() + 6., 2., DARKGREEN);
+            }
+            // Blink while invulnerable
+            if !ship.is_invulnerable(frame_t) || (frame_t * 8.) as i64 % 2 == 0 {
+                draw_ship(ship, player.color);
+            }
+        }
+
+        // HUD, one column per player
+        draw_text(&format!("Wave {}", game.wave), 10., 30., 30., DARKGRAY);
+        for (index, player) in players.iter().enumerate() {
+            let x = 10. + index as f32 * 260.;
+            let label = if player_count > 1 {
+                format!("{} ", player.name)
+            } else {
+                String::new()
+            };
+            draw_text(
+                &format!("{}Lives {}", label, player.lives),
+                x,
+                60.,
+                30.,
+                player.color,
+            );
+            draw_text(
+                &format!("{}Score {}", label, player.score),
+                x,
+                90.,
+                30.,
+                player.color,
+            );
+            player.power_ups.draw_timers(x, frame_t);
+        }
+        if game.next_wave_at.is_some() {
+            draw_centered_text(&format!("Wave {}", game.wave), screen_height() / 3., 50.);
+        }
 
         next_frame().await
     }
-}
\ No newline at end of file
+}
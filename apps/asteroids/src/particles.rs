@@ -0,0 +1,125 @@
+use macroquad::prelude::*;
+
+// Hard cap so a chain of explosions can't grow the pool without bounds
+const MAX_PARTICLES: usize = 8000;
+
+/// Describes a burst of particles, spawned around a direction by `ParticleSystem::emit`.
+pub struct Emitter {
+    pub count: usize,
+    // Speed in pixels per frame
+    pub speed: (f32, f32),
+    // Half-angle of the cone around the emit direction, in radians
+    pub spread: f32,
+    // Lifetime in seconds
+    pub lifetime: (f32, f32),
+    pub size: (f32, f32),
+    // Color at birth and at death, alpha is faded out on top of the gradient
+    pub colors: (Color, Color),
+}
+
+pub const EXHAUST: Emitter = Emitter {
+    count: 3,
+    speed: (1., 3.),
+    spread: 0.3,
+    lifetime: (0.15, 0.35),
+    size: (1.5, 3.),
+    colors: (Color::new(1., 0.9, 0.3, 1.), Color::new(0.8, 0.1, 0., 1.)),
+};
+
+pub const DEBRIS: Emitter = Emitter {
+    count: 25,
+    speed: (0.5, 3.),
+    spread: std::f32::consts::PI,
+    lifetime: (0.4, 1.2),
+    size: (1.5, 3.5),
+    colors: (Color::new(0.2, 0.2, 0.2, 1.), Color::new(0.5, 0.5, 0.5, 1.)),
+};
+
+pub const SHIP_EXPLOSION: Emitter = Emitter {
+    count: 150,
+    speed: (1., 6.),
+    spread: std::f32::consts::PI,
+    lifetime: (0.5, 1.8),
+    size: (2., 4.),
+    colors: (Color::new(1., 1., 0.6, 1.), Color::new(0.9, 0.2, 0., 1.)),
+};
+
+struct Particle {
+    pos: Vec2,
+    vel: Vec2,
+    age: f32,
+    lifetime: f32,
+    size: f32,
+    colors: (Color, Color),
+}
+
+pub struct ParticleSystem {
+    particles: Vec<Particle>,
+}
+
+fn lerp_color(a: Color, b: Color, t: f32) -> Color {
+    Color::new(
+        a.r + (b.r - a.r) * t,
+        a.g + (b.g - a.g) * t,
+        a.b + (b.b - a.b) * t,
+        a.a + (b.a - a.a) * t,
+    )
+}
+
+impl ParticleSystem {
+    pub fn new() -> Self {
+        ParticleSystem {
+            particles: Vec::with_capacity(MAX_PARTICLES),
+        }
+    }
+
+    /// Spawns `emitter.count` particles at `pos`, heading along `angle` (radians)
+    /// on top of `base_vel`, the velocity of whatever emitted them.
+    pub fn emit(&mut self, emitter: &Emitter, pos: Vec2, angle: f32, base_vel: Vec2) {
+        let count = emitter.count.min(MAX_PARTICLES - self.particles.len());
+        for _ in 0..count {
+            let dir = Vec2::from_angle(angle + rand::gen_range(-emitter.spread, emitter.spread));
+            self.particles.push(Particle {
+                pos,
+                vel: base_vel + dir * rand::gen_range(emitter.speed.0, emitter.speed.1),
+                age: 0.,
+                lifetime: rand::gen_range(emitter.lifetime.0, emitter.lifetime.1),
+                size: rand::gen_range(emitter.size.0, emitter.size.1),
+                colors: emitter.colors,
+            });
+        }
+    }
+
+    pub fn update(&mut self, dt: f32) {
+        // Order does not matter, swap_remove keeps this O(n) without shifting
+        let mut i = 0;
+        while i < self.particles.len() {
+            let particle = &mut self.particles[i];
+            particle.age += dt;
+            if particle.age >= particle.lifetime {
+                self.particles.swap_remove(i);
+                continue;
+            }
+            particle.pos += particle.vel;
+            particle.vel *= 0.98;
+            i += 1;
+        }
+    }
+
+    pub fn draw(&self) {
+        // Rectangles are a single quad each, circles would cost 20 triangles per particle
+        for particle in self.particles.iter() {
+            let t = particle.age / particle.lifetime;
+            let mut color = lerp_color(particle.colors.0, particle.colors.1, t);
+            color.a *= 1. - t;
+            let half = particle.size / 2.;
+            draw_rectangle(
+                particle.pos.x - half,
+                particle.pos.y - half,
+                particle.size,
+                particle.size,
+                color,
+            );
+        }
+    }
+}
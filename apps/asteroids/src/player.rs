@@ -0,0 +1,90 @@
+use macroquad::prelude::*;
+
+use crate::particles::{ParticleSystem, SHIP_EXPLOSION};
+use crate::powerups::PowerUps;
+use crate::{Ship, EXTRA_LIFE_SCORE, RESPAWN_DELAY, STARTING_LIVES};
+
+/// Which key does what for one player.
+pub struct Controls {
+    pub thrust: KeyCode,
+    pub left: KeyCode,
+    pub right: KeyCode,
+    pub shoot: KeyCode,
+    pub hyperspace: KeyCode,
+}
+
+pub const PLAYER_ONE_CONTROLS: Controls = Controls {
+    thrust: KeyCode::Up,
+    left: KeyCode::Left,
+    right: KeyCode::Right,
+    shoot: KeyCode::Space,
+    hyperspace: KeyCode::Down,
+};
+
+pub const PLAYER_TWO_CONTROLS: Controls = Controls {
+    thrust: KeyCode::W,
+    left: KeyCode::A,
+    right: KeyCode::D,
+    shoot: KeyCode::LeftShift,
+    hyperspace: KeyCode::S,
+};
+
+/// One ship and everything that belongs to whoever flies it.
+pub struct Player {
+    pub name: &'static str,
+    pub color: Color,
+    pub controls: Controls,
+    pub ship: Ship,
+    pub last_shot: f64,
+    pub last_hyperspace: f64,
+    pub power_ups: PowerUps,
+    pub lives: u32,
+    pub score: u32,
+    pub next_extra_life: u32,
+    // Set while the ship is destroyed, it respawns once this passed and its spawn point is clear
+    pub respawn_at: Option<f64>,
+}
+
+impl Player {
+    pub fn new(name: &'static str, color: Color, controls: Controls, ship: Ship) -> Self {
+        Player {
+            name,
+            color,
+            controls,
+            ship,
+            last_shot: get_time(),
+            last_hyperspace: 0.,
+            power_ups: PowerUps::new(),
+            lives: STARTING_LIVES,
+            score: 0,
+            next_extra_life: EXTRA_LIFE_SCORE,
+            respawn_at: None,
+        }
+    }
+
+    pub fn is_alive(&self) -> bool {
+        self.respawn_at.is_none()
+    }
+
+    /// Out of lives and done exploding, nothing left to do for this player.
+    pub fn is_out(&self, t: f64) -> bool {
+        self.lives == 0 && self.respawn_at.is_some_and(|respawn_at| t >= respawn_at)
+    }
+
+    pub fn add_score(&mut self, points: u32) {
+        self.score += points;
+        // Extra life for every few thousand points
+        while self.score >= self.next_extra_life {
+            self.lives += 1;
+            self.next_extra_life += EXTRA_LIFE_SCORE;
+        }
+    }
+
+    /// Blows the ship up and takes a life.
+    pub fn destroy(&mut self, t: f64, particles: &mut ParticleSystem) {
+        particles.emit(&SHIP_EXPLOSION, self.ship.pos, 0., self.ship.vel);
+        self.power_ups.clear();
+        self.lives -= 1;
+        self.respawn_at = Some(t + RESPAWN_DELAY);
+    }
+}
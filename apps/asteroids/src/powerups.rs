@@ -0,0 +1,197 @@
+use macroquad::prelude::*;
+
+use crate::wrap_around;
+
+const DROP_CHANCE: f32 = 0.08;
+const DROP_LIFETIME: f64 = 8.;
+pub const PICKUP_RADIUS: f32 = 10.;
+
+/// What the ship fires with and how well it is protected, the sum of all
+/// active power-ups applied on top of `Loadout::default()`.
+pub struct Loadout {
+    // Seconds between shots
+    pub reload: f64,
+    pub shots: u32,
+    // Angle between neighbouring shots of one volley, in radians
+    pub spread: f32,
+    pub piercing: bool,
+    pub shield: bool,
+}
+
+impl Default for Loadout {
+    fn default() -> Self {
+        Loadout {
+            reload: 0.5,
+            shots: 1,
+            spread: 0.,
+            piercing: false,
+            shield: false,
+        }
+    }
+}
+
+pub struct PowerUp {
+    pub name: &'static str,
+    // Shown on the pickup
+    pub letter: &'static str,
+    pub color: Color,
+    pub duration: f64,
+    pub apply: fn(&mut Loadout),
+}
+
+impl PowerUp {
+    fn grants_shield(&self) -> bool {
+        let mut loadout = Loadout::default();
+        (self.apply)(&mut loadout);
+        loadout.shield
+    }
+}
+
+// Add new power-ups here, drops pick from this list at random
+pub static POWER_UPS: &[PowerUp] = &[
+    PowerUp {
+        name: "Spread shot",
+        letter: "S",
+        color: BLUE,
+        duration: 10.,
+        apply: |loadout| {
+            loadout.shots = 3;
+            loadout.spread = 0.2;
+        },
+    },
+    PowerUp {
+        name: "Rapid fire",
+        letter: "R",
+        color: RED,
+        duration: 8.,
+        apply: |loadout| loadout.reload = 0.15,
+    },
+    PowerUp {
+        name: "Piercing",
+        letter: "P",
+        color: PURPLE,
+        duration: 10.,
+        apply: |loadout| loadout.piercing = true,
+    },
+    PowerUp {
+        name: "Shield",
+        letter: "O",
+        color: DARKGREEN,
+        duration: 15.,
+        apply: |loadout| loadout.shield = true,
+    },
+];
+
+/// A power-up floating around, waiting to be picked up by the ship.
+pub struct Pickup {
+    pub pos: Vec2,
+    vel: Vec2,
+    pub power_up: &'static PowerUp,
+    expires_at: f64,
+}
+
+impl Pickup {
+    /// Rolls the dice for a drop where an asteroid was destroyed.
+    pub fn roll(pos: Vec2, t: f64) -> Option<Pickup> {
+        if rand::gen_range(0., 1.) >= DROP_CHANCE {
+            return None;
+        }
+        Some(Pickup {
+            pos,
+            vel: Vec2::from_angle(rand::gen_range(0., std::f32::consts::TAU)) * 0.5,
+            power_up: &POWER_UPS[rand::gen_range(0, POWER_UPS.len())],
+            expires_at: t + DROP_LIFETIME,
+        })
+    }
+
+    /// Drifts the drop along, returns false once it expired.
+    pub fn update(&mut self, t: f64) -> bool {
+        self.pos += self.vel;
+        self.pos = wrap_around(&self.pos);
+        t < self.expires_at
+    }
+
+    pub fn draw(&self, t: f64) {
+        // Blink during the last two seconds
+        if self.expires_at - t < 2. && (t * 8.) as i64 % 2 == 0 {
+            return;
+        }
+        draw_circle_lines(
+            self.pos.x,
+            self.pos.y,
+            PICKUP_RADIUS,
+            2.,
+            self.power_up.color,
+        );
+        let size = measure_text(self.power_up.letter, None, 20, 1.);
+        draw_text(
+            self.power_up.letter,
+            self.pos.x - size.width / 2.,
+            self.pos.y + size.height / 2.,
+            20.,
+            self.power_up.color,
+        );
+    }
+}
+
+struct Active {
+    power_up: &'static PowerUp,
+    until: f64,
+}
+
+/// Power-ups the ship is currently running with.
+pub struct PowerUps {
+    active: Vec<Active>,
+}
+
+impl PowerUps {
+    pub fn new() -> Self {
+        PowerUps { active: Vec::new() }
+    }
+
+    // Picking up one that is already running restarts its timer
+    pub fn collect(&mut self, power_up: &'static PowerUp, t: f64) {
+        self.active
+            .retain(|active| active.power_up.name != power_up.name);
+        self.active.push(Active {
+            power_up,
+            until: t + power_up.duration,
+        });
+    }
+
+    pub fn update(&mut self, t: f64) {
+        self.active.retain(|active| t < active.until);
+    }
+
+    pub fn clear(&mut self) {
+        self.active.clear();
+    }
+
+    pub fn loadout(&self) -> Loadout {
+        let mut loadout = Loadout::default();
+        for active in self.active.iter() {
+            (active.power_up.apply)(&mut loadout);
+        }
+        loadout
+    }
+
+    /// Uses up the shield, returns false if there was none to absorb the hit.
+    pub fn absorb_hit(&mut self) -> bool {
+        let before = self.active.len();
+        self.active
+            .retain(|active| !active.power_up.grants_shield());
+        self.active.len() != before
+    }
+
+    /// Name and a bar with the time left for each active power-up, stacked up
+    /// from the bottom of the screen starting at `x`.
+    pub fn draw_timers(&self, x: f32, t: f64) {
+        for (i, active) in self.active.iter().enumerate() {
+            let y = screen_height() - 20. - i as f32 * 30.;
+            let left = ((active.until - t) / active.power_up.duration) as f32;
+            draw_text(active.power_up.name, x, y, 24., active.power_up.color);
+            draw_rectangle(x + 130., y - 14., 100. * left, 12., active.power_up.color);
+            draw_rectangle_lines(x + 130., y - 14., 100., 12., 2., DARKGRAY);
+        }
+    }
+}
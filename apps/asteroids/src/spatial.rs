@@ -0,0 +1,111 @@
+use macroquad::prelude::*;
+
+/// Uniform grid over a wrapping world, used as collision broadphase. Objects are
+/// stored by index into whatever list the caller keeps, in every cell their
+/// bounding circle touches. Cells at one edge of the world neighbour the cells
+/// at the opposite edge, so lookups work across the seam.
+pub struct SpatialHash {
+    world: Vec2,
+    cols: i32,
+    rows: i32,
+    // Cells are stretched a little so a whole number of them covers the world exactly
+    cell: Vec2,
+    cells: Vec<Vec<usize>>,
+}
+
+impl SpatialHash {
+    pub fn new(world: Vec2, cell_size: f32) -> Self {
+        let cols = (world.x / cell_size).ceil().max(1.) as i32;
+        let rows = (world.y / cell_size).ceil().max(1.) as i32;
+        SpatialHash {
+            world,
+            cols,
+            rows,
+            cell: Vec2::new(world.x / cols as f32, world.y / rows as f32),
+            cells: (0..cols * rows).map(|_| Vec::new()).collect(),
+        }
+    }
+
+    /// Empties the grid for the next frame, keeping the cell allocations
+    /// unless the world was resized.
+    pub fn reset(&mut self, world: Vec2, cell_size: f32) {
+        if world != self.world {
+            *self = SpatialHash::new(world, cell_size);
+        } else {
+            self.cells.iter_mut().for_each(Vec::clear);
+        }
+    }
+
+    // Indices of all cells touched by the box around the circle, wrapped around the edges
+    fn cells_around(&self, pos: Vec2, radius: f32) -> impl Iterator<Item = usize> {
+        let (cols, rows) = (self.cols, self.rows);
+        let x0 = ((pos.x - radius) / self.cell.x).floor() as i32;
+        let y0 = ((pos.y - radius) / self.cell.y).floor() as i32;
+        // A circle wider than the world touches every column once, not more
+        let x1 = (((pos.x + radius) / self.cell.x).floor() as i32).min(x0 + cols - 1);
+        let y1 = (((pos.y + radius) / self.cell.y).floor() as i32).min(y0 + rows - 1);
+        (y0..=y1).flat_map(move |y| {
+            (x0..=x1).map(move |x| (y.rem_euclid(rows) * cols + x.rem_euclid(cols)) as usize)
+        })
+    }
+
+    pub fn insert(&mut self, index: usize, pos: Vec2, radius: f32) {
+        for cell in self.cells_around(pos, radius) {
+            self.cells[cell].push(index);
+        }
+    }
+
+    /// Fills `out` with every index stored in a cell the circle touches, each once.
+    /// These are only candidates, the caller still does the exact test.
+    pub fn query(&self, pos: Vec2, radius: f32, out: &mut Vec<usize>) {
+        out.clear();
+        for cell in self.cells_around(pos, radius) {
+            out.extend_from_slice(&self.cells[cell]);
+        }
+        out.sort_unstable();
+        out.dedup();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn query_finds_nearby_and_skips_far() {
+        let mut grid = SpatialHash::new(Vec2::new(800., 600.), 50.);
+        grid.insert(0, Vec2::new(100., 100.), 10.);
+        grid.insert(1, Vec2::new(700., 500.), 10.);
+        let mut out = Vec::new();
+        grid.query(Vec2::new(110., 100.), 5., &mut out);
+        assert_eq!(out, [0]);
+    }
+
+    #[test]
+    fn query_wraps_across_the_seam() {
+        let mut grid = SpatialHash::new(Vec2::new(800., 600.), 50.);
+        grid.insert(0, Vec2::new(795., 595.), 10.);
+        let mut out = Vec::new();
+        grid.query(Vec2::new(2., 3.), 5., &mut out);
+        assert_eq!(out, [0]);
+    }
+
+    #[test]
+    fn large_objects_are_reported_once() {
+        let mut grid = SpatialHash::new(Vec2::new(800., 600.), 50.);
+        grid.insert(3, Vec2::new(400., 300.), 2000.);
+        let mut out = Vec::new();
+        grid.query(Vec2::new(400., 300.), 200., &mut out);
+        assert_eq!(out, [3]);
+    }
+
+    #[test]
+    fn reset_empties_the_grid() {
+        let mut grid = SpatialHash::new(Vec2::new(800., 600.), 50.);
+        grid.insert(0, Vec2::new(100., 100.), 10.);
+        grid.reset(Vec2::new(800., 600.), 50.);
+        let mut out = vec![7];
+        grid.query(Vec2::new(100., 100.), 10., &mut out);
+        assert!(out.is_empty());
+    }
+}
@@ -0,0 +1,149 @@
+use macroquad::prelude::*;
+
+use crate::{draw_polygon_lines, seam_delta, wrap_around, Bullet};
+
+const LARGE_UFO_SPEED: f32 = 1.5;
+const SMALL_UFO_SPEED: f32 = 2.5;
+const UFO_BULLET_SPEED: f32 = 5.;
+const TURN_INTERVAL: f64 = 1.5;
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum UfoKind {
+    // Slow, fires in random directions
+    Large,
+    // Fast, aims at the ship and gets better at it every wave
+    Small,
+}
+
+pub struct Ufo {
+    pub pos: Vec2,
+    pub vel: Vec2,
+    pub kind: UfoKind,
+    pub collided: bool,
+    // Horizontal distance covered, the UFO leaves once it crossed the whole screen
+    travelled: f32,
+    next_turn: f64,
+    last_shot: f64,
+}
+
+impl Ufo {
+    // Enters at the left or right edge and heads for the other side
+    pub fn spawn(kind: UfoKind, t: f64) -> Self {
+        let speed = match kind {
+            UfoKind::Large => LARGE_UFO_SPEED,
+            UfoKind::Small => SMALL_UFO_SPEED,
+        };
+        let from_left = rand::gen_range(0, 2) == 0;
+        Ufo {
+            pos: Vec2::new(
+                if from_left { 0. } else { screen_width() },
+                rand::gen_range(0.2, 0.8) * screen_height(),
+            ),
+            vel: Vec2::new(if from_left { speed } else { -speed }, 0.),
+            kind,
+            collided: false,
+            travelled: 0.,
+            next_turn: t + TURN_INTERVAL,
+            last_shot: t,
+        }
+    }
+
+    pub fn size(&self) -> f32 {
+        match self.kind {
+            UfoKind::Large => 20.,
+            UfoKind::Small => 10.,
+        }
+    }
+
+    pub fn points(&self) -> u32 {
+        match self.kind {
+            UfoKind::Large => 200,
+            UfoKind::Small => 1000,
+        }
+    }
+
+    /// Moves the UFO along its path, returns false once it left the screen.
+    pub fn update(&mut self, t: f64) -> bool {
+        // Zig-zag: every interval pick a new vertical heading, keep the horizontal one
+        if t >= self.next_turn {
+            let speed = self.vel.x.abs();
+            self.vel.y = speed * [-1., 0., 1.][rand::gen_range(0, 3)];
+            self.next_turn = t + TURN_INTERVAL;
+        }
+        self.pos += self.vel;
+        self.pos = wrap_around(&self.pos);
+        self.travelled += self.vel.x.abs();
+        self.travelled < screen_width()
+    }
+
+    /// Fires at the ship (if there is one to aim at) once the UFO's reload time passed.
+    pub fn shoot(&mut self, t: f64, target: Option<Vec2>, wave: u32) -> Option<Bullet> {
+        let reload = match self.kind {
+            UfoKind::Large => 1.5,
+            UfoKind::Small => 1.,
+        };
+        if t - self.last_shot < reload {
+            return None;
+        }
+        self.last_shot = t;
+
+        let angle = match (self.kind, target) {
+            (UfoKind::Small, Some(target)) => {
+                // Aim error shrinks every wave, down to a near-perfect shot
+                let error = (0.5 - 0.05 * wave as f32).max(0.05);
+                let aim = seam_delta(self.pos, target);
+                aim.y.atan2(aim.x) + rand::gen_range(-error, error)
+            }
+            _ => rand::gen_range(0., std::f32::consts::TAU),
+        };
+        let dir = Vec2::from_angle(angle);
+        Some(Bullet {
+            pos: self.pos + dir * self.size(),
+            vel: dir * UFO_BULLET_SPEED,
+            shot_at: t,
+            collided: false,
+            owner: None,
+            piercing: false,
+        })
+    }
+
+    /// Outline of the saucer, used for drawing and collisions.
+    pub fn polygon(&self) -> Vec<Vec2> {
+        let s = self.size();
+        [
+            (-1., 0.),
+            (-0.5, -0.35),
+            (-0.25, -0.7),
+            (0.25, -0.7),
+            (0.5, -0.35),
+            (1., 0.),
+            (0.5, 0.35),
+            (-0.5, 0.35),
+        ]
+        .iter()
+        .map(|(x, y)| self.pos + Vec2::new(x * s, y * s))
+        .collect()
+    }
+}
+
+pub fn draw_ufo(ufo: &Ufo) {
+    let polygon = ufo.polygon();
+    draw_polygon_lines(&polygon);
+    // Rim lines between the dome and the hull
+    draw_line(
+        polygon[0].x,
+        polygon[0].y,
+        polygon[5].x,
+        polygon[5].y,
+        2.,
+        BLACK,
+    );
+    draw_line(
+        polygon[1].x,
+        polygon[1].y,
+        polygon[4].x,
+        polygon[4].y,
+        2.,
+        BLACK,
+    );
+}
@@ -0,0 +1,86 @@
+use macroquad::prelude::*;
+
+/// A cell of the sprite sheet and how long it stays up, in seconds.
+pub struct AnimationFrame {
+    pub index: usize,
+    pub time: f32,
+}
+
+pub struct Animation {
+    pub frames: &'static [AnimationFrame],
+}
+
+/// Plays one of a set of animations from a sprite sheet whose cells form a
+/// `columns` wide grid, numbered left to right and top to bottom.
+pub struct Animations {
+    animations: &'static [Animation],
+    cell_size: Vec2,
+    columns: usize,
+    current_animation: usize,
+    current_frame: usize,
+    time_left: f32,
+}
+
+impl Animations {
+    pub fn new(animations: &'static [Animation], sheet: &Texture2D, columns: usize, rows: usize) -> Self {
+        Animations {
+            animations,
+            cell_size: vec2(sheet.width() / columns as f32, sheet.height() / rows as f32),
+            columns,
+            current_animation: 0,
+            current_frame: 0,
+            time_left: animations[0].frames[0].time,
+        }
+    }
+
+    /// Switches to another animation, starting it from its first frame.
+    pub fn play(&mut self, animation: usize) {
+        if animation != self.current_animation {
+            self.current_animation = animation;
+            self.current_frame = 0;
+            self.time_left = self.animations[animation].frames[0].time;
+        }
+    }
+
+    /// Moves on by `dt` seconds, looping at the end.
+    pub fn update(&mut self, dt: f32) {
+        let frames = self.animations[self.current_animation].frames;
+        self.time_left -= dt;
+        while self.time_left <= 0.0 {
+            self.current_frame = (self.current_frame + 1) % frames.len();
+            self.time_left += frames[self.current_frame].time;
+        }
+    }
+
+    /// Where the current frame sits on the sheet, for `DrawTextureParams::source`.
+    pub fn source(&self) -> Rect {
+        self.cell(self.animations[self.current_animation].frames[self.current_frame].index)
+    }
+
+    pub fn cell(&self, index: usize) -> Rect {
+        let x = (index % self.columns) as f32 * self.cell_size.x;
+        let y = (index / self.columns) as f32 * self.cell_size.y;
+        Rect::new(x, y, self.cell_size.x, self.cell_size.y)
+    }
+}
+
+/// Tilts a sprite with its vertical speed: `angle_up` when climbing at
+/// `velocity_max` or faster, `angle_down` when falling that fast, in between
+/// otherwise. Angles are counterclockwise in radians, like in the Bevy port.
+pub struct VelocityRotator {
+    pub angle_up: f32,
+    pub angle_down: f32,
+    pub velocity_max: f32,
+}
+
+impl VelocityRotator {
+    /// Rotation for `DrawTextureParams::rotation` at `velocity`, positive is falling.
+    pub fn rotation(&self, velocity: f32) -> f32 {
+        // -1 falling fast to 1 climbing fast, then 0 to 1
+        let climb = (-velocity / self.velocity_max).clamp(-1.0, 1.0);
+        let t = (climb + 1.0) * 0.5;
+        let angle = (1.0 - t) * self.angle_down + t * self.angle_up;
+        // The screen's y axis points down, so macroquad turns clockwise
+        -angle
+    }
+}
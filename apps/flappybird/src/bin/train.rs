@@ -0,0 +1,134 @@
+//! Evolves a brain for the "AI plays" mode, headless and on every core:
+//!
+//! ```text
+//! cargo run --release --bin train -- [generations] [population] [output]
+//! ```
+//!
+//! Defaults to 100 generations of 1000 brains, saved to public/brain.txt after
+//! every generation so stopping early still leaves the best one so far.
+
+use std::thread;
+
+use macroquad::math::{vec2, Vec2};
+use macroquad::rand::RandGenerator;
+use myapp::brain::Brain;
+use myapp::difficulty::PRESETS;
+use myapp::game::{new_seed, GameState, ObstacleMode};
+
+const MAX_FRAMES: u32 = 60 * 90; // a brain that survives a minute and a half on a course has mastered it
+const ELITE: f32 = 0.02; // share of the best brains that go on unchanged
+const PARENTS: f32 = 0.2; // share of the best brains that get children
+const MUTATION_RATE: f32 = 0.1;
+const MUTATION_STRENGTH: f32 = 0.5;
+const COIN_BONUS: f32 = 100.0; // fitness per point, on top of the distance flown
+
+// The bird as the game draws it: a 32 pixel cell of bird.png at one and a half times the size
+const BIRD_SIZE: Vec2 = vec2(48.0, 48.0);
+
+// Every brain flies every course: each obstacle mode at each difficulty
+fn courses(seed: u64) -> Vec<GameState> {
+    [ObstacleMode::Classic, ObstacleMode::Floating]
+        .iter()
+        .flat_map(|&obstacles| {
+            PRESETS
+                .iter()
+                .map(move |&difficulty| (difficulty, obstacles))
+        })
+        .enumerate()
+        .map(|(i, (difficulty, obstacles))| {
+            GameState::with_seed(
+                difficulty,
+                obstacles,
+                seed.wrapping_add(i as u64),
+                BIRD_SIZE,
+            )
+        })
+        .collect()
+}
+
+fn fitness(brain: &Brain, seed: u64) -> f32 {
+    courses(seed)
+        .into_iter()
+        .map(|mut game| {
+            let mut frames = 0;
+            while frames < MAX_FRAMES && game.update(brain.flaps(&game.observe())) {
+                frames += 1;
+            }
+            game.distance + game.score as f32 * COIN_BONUS
+        })
+        .sum()
+}
+
+// Splits the population over all cores
+fn evaluate(population: &[Brain], seed: u64) -> Vec<f32> {
+    let threads = thread::available_parallelism().map_or(1, |n| n.get());
+    let chunk = population.len().div_ceil(threads);
+    thread::scope(|scope| {
+        let handles: Vec<_> = population
+            .chunks(chunk)
+            .map(|brains| {
+                scope.spawn(move || {
+                    brains
+                        .iter()
+                        .map(|brain| fitness(brain, seed))
+                        .collect::<Vec<f32>>()
+                })
+            })
+            .collect();
+        handles
+            .into_iter()
+            .flat_map(|handle| handle.join().unwrap())
+            .collect()
+    })
+}
+
+// Keeps the elite, fills up with mutated crossovers of the best brains
+fn next_generation(ranked: &[Brain], rng: &RandGenerator) -> Vec<Brain> {
+    let elite = ((ranked.len() as f32 * ELITE) as usize).max(1);
+    let parents = ((ranked.len() as f32 * PARENTS) as usize).max(2);
+    let mut next = ranked[..elite].to_vec();
+    while next.len() < ranked.len() {
+        let a = &ranked[rng.gen_range(0, parents)];
+        let b = &ranked[rng.gen_range(0, parents)];
+        next.push(
+            a.crossover(b, rng)
+                .mutated(rng, MUTATION_RATE, MUTATION_STRENGTH),
+        );
+    }
+    next
+}
+
+fn main() {
+    let args: Vec<String> = std::env::args().collect();
+    let generations: usize = args.get(1).and_then(|arg| arg.parse().ok()).unwrap_or(100);
+    let size: usize = args
+        .get(2)
+        .and_then(|arg| arg.parse().ok())
+        .unwrap_or(1000)
+        .max(2);
+    let output = args.get(3).map_or("public/brain.txt", String::as_str);
+
+    let rng = RandGenerator::new();
+    rng.srand(new_seed());
+    let mut population: Vec<Brain> = (0..size).map(|_| Brain::random(&rng)).collect();
+
+    for generation in 1..=generations {
+        // Fresh courses every generation, so brains learn to fly rather than memorize
+        let seed = rng.gen_range(0, u32::MAX) as u64;
+        let scores = evaluate(&population, seed);
+        let mut ranked: Vec<(f32, Brain)> = scores.into_iter().zip(population).collect();
+        ranked.sort_by(|a, b| b.0.total_cmp(&a.0));
+
+        let average = ranked.iter().map(|(score, _)| score).sum::<f32>() / ranked.len() as f32;
+        println!(
+            "generation {}: best {:.0}, average {:.0}",
+            generation, ranked[0].0, average
+        );
+        if let Err(err) = std::fs::write(output, ranked[0].1.serialize()) {
+            eprintln!("could not save {}: {}", output, err);
+        }
+
+        let ranked: Vec<Brain> = ranked.into_iter().map(|(_, brain)| brain).collect();
+        population = next_generation(&ranked, &rng);
+    }
+}
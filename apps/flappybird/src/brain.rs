@@ -0,0 +1,101 @@
+//! A small neural network that flies the bird. Evolved by bin/train.rs and loaded
+//! by the game for the "AI plays" mode.
+
+use macroquad::rand::RandGenerator;
+
+use crate::game::Observation;
+
+const INPUTS: usize = 5;
+const HIDDEN: usize = 6;
+// Every hidden neuron has a weight per input plus a bias, the output one a weight per hidden neuron plus a bias
+const WEIGHTS: usize = HIDDEN * (INPUTS + 1) + HIDDEN + 1;
+
+/// One fully connected hidden layer of tanh neurons, flaps when the output is
+/// positive. Stored as a "brain <inputs> <hidden>" line and then one weight per line.
+#[derive(Clone)]
+pub struct Brain {
+    weights: Vec<f32>,
+}
+
+// Standard normal sample, Box-Muller
+fn gaussian(rng: &RandGenerator) -> f32 {
+    let u1: f32 = rng.gen_range(f32::EPSILON, 1.0);
+    let u2: f32 = rng.gen_range(0.0, 1.0);
+    (-2.0 * u1.ln()).sqrt() * (std::f32::consts::TAU * u2).cos()
+}
+
+impl Brain {
+    pub fn random(rng: &RandGenerator) -> Brain {
+        Brain {
+            weights: (0..WEIGHTS).map(|_| gaussian(rng)).collect(),
+        }
+    }
+
+    /// Each weight taken from either parent at random.
+    pub fn crossover(&self, other: &Brain, rng: &RandGenerator) -> Brain {
+        let weights = self
+            .weights
+            .iter()
+            .zip(&other.weights)
+            .map(|(&a, &b)| if rng.gen_range(0, 2) == 0 { a } else { b })
+            .collect();
+        Brain { weights }
+    }
+
+    /// A copy with a share `rate` of the weights nudged by up to about `strength`.
+    pub fn mutated(&self, rng: &RandGenerator, rate: f32, strength: f32) -> Brain {
+        let weights = self
+            .weights
+            .iter()
+            .map(|&weight| {
+                if rng.gen_range(0.0, 1.0) < rate {
+                    weight + gaussian(rng) * strength
+                } else {
+                    weight
+                }
+            })
+            .collect();
+        Brain { weights }
+    }
+
+    pub fn flaps(&self, observation: &Observation) -> bool {
+        let inputs = [
+            observation.height,
+            observation.velocity,
+            observation.distance,
+            observation.opening_top,
+            observation.opening_bottom,
+        ];
+        let (hidden_weights, output_weights) = self.weights.split_at(HIDDEN * (INPUTS + 1));
+        let output = hidden_weights
+            .chunks(INPUTS + 1)
+            .zip(output_weights)
+            .map(|(neuron, &weight)| {
+                let sum: f32 = neuron.iter().zip(&inputs).map(|(w, x)| w * x).sum();
+                (sum + neuron[INPUTS]).tanh() * weight
+            })
+            .sum::<f32>()
+            + output_weights[HIDDEN];
+        output > 0.0
+    }
+
+    /// None if the data is broken or was saved for a different network shape.
+    pub fn parse(data: &str) -> Option<Brain> {
+        let mut lines = data.lines();
+        if lines.next()? != format!("brain {} {}", INPUTS, HIDDEN) {
+            return None;
+        }
+        let weights = lines
+            .map(|line| line.trim().parse().ok())
+            .collect::<Option<Vec<f32>>>()?;
+        (weights.len() == WEIGHTS).then_some(Brain { weights })
+    }
+
+    pub fn serialize(&self) -> String {
+        let mut data = format!("brain {} {}\n", INPUTS, HIDDEN);
+        for weight in &self.weights {
+            data.push_str(&format!("{}\n", weight));
+        }
+        data
+    }
+}
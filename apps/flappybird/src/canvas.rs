@@ -0,0 +1,68 @@
+use macroquad::prelude::*;
+
+/// Fixed-size drawing surface. The game draws in virtual coordinates between
+/// `begin` and `end`, which scales the result to fit the window and fills the
+/// leftover space with black bars.
+pub struct VirtualCanvas {
+    size: Vec2,
+    target: RenderTarget,
+    camera: Camera2D,
+}
+
+impl VirtualCanvas {
+    pub fn new(width: f32, height: f32) -> Self {
+        let target = render_target(width as u32, height as u32);
+        target.texture.set_filter(FilterMode::Linear);
+        let mut camera = Camera2D::from_display_rect(Rect::new(0.0, 0.0, width, height));
+        camera.render_target = Some(target.clone());
+        VirtualCanvas {
+            size: vec2(width, height),
+            target,
+            camera,
+        }
+    }
+
+    // Scale and top-left corner of the canvas in the window
+    fn placement(&self) -> (f32, Vec2) {
+        let scale = (screen_width() / self.size.x).min(screen_height() / self.size.y);
+        let offset = (vec2(screen_width(), screen_height()) - self.size * scale) / 2.0;
+        (scale, offset)
+    }
+
+    /// Everything drawn from here on ends up on the canvas.
+    pub fn begin(&self) {
+        set_camera(&self.camera);
+    }
+
+    /// Puts the canvas on the window, letterboxed.
+    pub fn end(&self) {
+        set_default_camera();
+        clear_background(BLACK);
+        let (scale, offset) = self.placement();
+        draw_texture_ex(
+            &self.target.texture,
+            offset.x,
+            offset.y,
+            WHITE,
+            DrawTextureParams {
+                dest_size: Some(self.size * scale),
+                // Render targets come out upside down
+                flip_y: true,
+                ..Default::default()
+            },
+        );
+    }
+
+    /// Maps a window position into game coordinates, None if it is on the black bars.
+    pub fn to_game(&self, window_pos: Vec2) -> Option<Vec2> {
+        let (scale, offset) = self.placement();
+        let pos = (window_pos - offset) / scale;
+        let inside = pos.x >= 0.0 && pos.y >= 0.0 && pos.x <= self.size.x && pos.y <= self.size.y;
+        inside.then_some(pos)
+    }
+
+    /// Mouse position in game coordinates, None while it is over the black bars.
+    pub fn mouse_position(&self) -> Option<Vec2> {
+        self.to_game(Vec2::from(mouse_position()))
+    }
+}
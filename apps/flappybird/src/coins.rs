@@ -0,0 +1,137 @@
+use macroquad::prelude::*;
+use macroquad::rand::RandGenerator;
+
+use crate::game::{is_circle_colliding_with_rect, Coin, Pipe};
+
+const COIN_RADIUS: f32 = 15.0;
+const COIN_STEP: f32 = 50.0;  // distance between neighbouring coins of a pattern
+const PATTERN_CHANCE: f32 = 0.7;  // share of the stretches between obstacles that get coins
+const CLEARANCE: f32 = 20.0;  // room kept free between a coin and any obstacle
+
+#[derive(Clone, Copy)]
+enum Pattern {
+    Line,
+    Arc,
+    GapTrail,
+}
+
+/// Places coin patterns in the free stretch behind a freshly spawned obstacle.
+/// It has its own generator, so the same seed always gives the same coins.
+pub struct CoinSpawner {
+    rng: RandGenerator,
+}
+
+impl CoinSpawner {
+    pub fn new(seed: u64) -> Self {
+        let rng = RandGenerator::new();
+        rng.srand(seed);
+        CoinSpawner { rng }
+    }
+
+    /// Coins between `from_x` and `to_x`, or through `gap` (the opening between a
+    /// pair of pipes) if there is one. Coins that would touch a pipe are dropped.
+    pub fn spawn(&self, pipes: &[Pipe], from_x: f32, to_x: f32, screen_height: f32, gap: Option<Rect>) -> Vec<Coin> {
+        if self.rng.gen_range(0.0, 1.0) >= PATTERN_CHANCE || to_x - from_x < COIN_STEP {
+            return Vec::new();
+        }
+
+        let pattern = match (gap, self.rng.gen_range(0, 3)) {
+            (Some(_), 0) => Pattern::GapTrail,
+            (_, 1) => Pattern::Arc,
+            _ => Pattern::Line,
+        };
+        let margin = COIN_RADIUS + CLEARANCE;
+        let positions: Vec<Vec2> = match (pattern, gap) {
+            (Pattern::GapTrail, Some(gap)) => {
+                // Straight through the middle of the opening, starting a bit before it
+                let y = gap.center().y;
+                let count = ((gap.w + 3.0 * COIN_STEP) / COIN_STEP) as usize;
+                (0..count).map(|i| vec2(gap.x - COIN_STEP + i as f32 * COIN_STEP, y)).collect()
+            }
+            (Pattern::Arc, _) => {
+                // A hump, roughly the path of a flap
+                let count = self.rng.gen_range(5, 9).min(((to_x - from_x) / COIN_STEP) as usize + 1);
+                let height = self.rng.gen_range(80.0, 200.0);
+                let base = self.rng.gen_range(margin + height, screen_height - margin);
+                let start = self.rng.gen_range(from_x, to_x - (count - 1) as f32 * COIN_STEP);
+                (0..count)
+                    .map(|i| {
+                        let t = i as f32 / (count - 1).max(1) as f32;
+                        vec2(start + i as f32 * COIN_STEP, base - (t * std::f32::consts::PI).sin() * height)
+                    })
+                    .collect()
+            }
+            _ => {
+                let count = self.rng.gen_range(3, 7).min(((to_x - from_x) / COIN_STEP) as usize + 1);
+                let y = self.rng.gen_range(margin, screen_height - margin);
+                let start = self.rng.gen_range(from_x, to_x - (count - 1) as f32 * COIN_STEP);
+                (0..count).map(|i| vec2(start + i as f32 * COIN_STEP, y)).collect()
+            }
+        };
+
+        positions
+            .into_iter()
+            .filter(|&position| {
+                !pipes.iter().any(|pipe| {
+                    is_circle_colliding_with_rect(position, COIN_RADIUS + CLEARANCE, pipe.position, pipe.size)
+                })
+            })
+            .map(|position| Coin { position, radius: COIN_RADIUS })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::PipeKind;
+
+    const SCREEN_HEIGHT: f32 = 900.0;
+
+    fn pipe(x: f32, y: f32, w: f32, h: f32) -> Pipe {
+        Pipe { position: vec2(x, y), size: vec2(w, h), kind: PipeKind::Floating, passed: false }
+    }
+
+    // A classic pair with its gap, and a floating block further along the stretch
+    fn obstacles() -> (Vec<Pipe>, Rect) {
+        let gap = Rect::new(100.0, 350.0, 80.0, 200.0);
+        let pipes = vec![
+            pipe(gap.x, 0.0, gap.w, gap.y),
+            pipe(gap.x, gap.bottom(), gap.w, SCREEN_HEIGHT - gap.bottom()),
+            pipe(400.0, 300.0, 60.0, 250.0),
+        ];
+        (pipes, gap)
+    }
+
+    fn positions(coins: &[Coin]) -> Vec<Vec2> {
+        coins.iter().map(|coin| coin.position).collect()
+    }
+
+    #[test]
+    fn same_seed_gives_same_coins() {
+        let (pipes, gap) = obstacles();
+        let a = CoinSpawner::new(42);
+        let b = CoinSpawner::new(42);
+        for _ in 0..100 {
+            let coins_a = a.spawn(&pipes, 200.0, 700.0, SCREEN_HEIGHT, Some(gap));
+            let coins_b = b.spawn(&pipes, 200.0, 700.0, SCREEN_HEIGHT, Some(gap));
+            assert_eq!(positions(&coins_a), positions(&coins_b));
+        }
+    }
+
+    #[test]
+    fn coins_never_touch_an_obstacle() {
+        let (pipes, gap) = obstacles();
+        let spawner = CoinSpawner::new(7);
+        let mut spawned = 0;
+        for _ in 0..1000 {
+            for coin in spawner.spawn(&pipes, 200.0, 700.0, SCREEN_HEIGHT, Some(gap)) {
+                spawned += 1;
+                for pipe in &pipes {
+                    assert!(!is_circle_colliding_with_rect(coin.position, coin.radius, pipe.position, pipe.size));
+                }
+            }
+        }
+        assert!(spawned > 0);
+    }
+}
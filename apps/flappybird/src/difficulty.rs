@@ -0,0 +1,58 @@
+/// How fast the game starts and how quickly it ramps up. The base values get
+/// multiplied by a factor that grows with score and time, up to `max_multiplier`.
+/// Obstacle spacing and size only ramp with distance, so a seeded course stays
+/// the same however it is flown.
+#[derive(Clone, Copy)]
+pub struct Difficulty {
+    pub name: &'static str,
+    // Scale on the scroll speed
+    pub speed: f32,
+    // Scale on the distance between obstacles
+    pub spacing: f32,
+    // Scale on obstacle height, taller obstacles leave smaller gaps
+    pub obstacle_size: f32,
+    // Multiplier added per collected coin
+    pub per_coin: f32,
+    // Multiplier added per second survived
+    pub per_second: f32,
+    pub max_multiplier: f32,
+}
+
+pub const EASY: Difficulty = Difficulty {
+    name: "Easy",
+    speed: 0.8,
+    spacing: 1.2,
+    obstacle_size: 0.8,
+    per_coin: 0.03,
+    per_second: 0.005,
+    max_multiplier: 1.5,
+};
+
+pub const NORMAL: Difficulty = Difficulty {
+    name: "Normal",
+    speed: 1.0,
+    spacing: 1.0,
+    obstacle_size: 1.0,
+    per_coin: 0.05,
+    per_second: 0.01,
+    max_multiplier: 2.0,
+};
+
+pub const HARD: Difficulty = Difficulty {
+    name: "Hard",
+    speed: 1.25,
+    spacing: 0.85,
+    obstacle_size: 1.15,
+    per_coin: 0.08,
+    per_second: 0.015,
+    max_multiplier: 2.5,
+};
+
+pub const PRESETS: [Difficulty; 3] = [EASY, NORMAL, HARD];
+
+impl Difficulty {
+    pub fn multiplier(&self, score: i32, seconds: f32) -> f32 {
+        let ramp = self.per_coin * score as f32 + self.per_second * seconds;
+        (1.0 + ramp).min(self.max_multiplier)
+    }
+}
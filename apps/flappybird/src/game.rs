@@ -0,0 +1,581 @@
+//! The game itself without a window: obstacles, coins, the bird and the rules
+//! that move them along a frame at a time. Drawing and input live in main.rs,
+//! so the same rules run in the browser and in the headless trainer.
+
+use macroquad::prelude::*;
+use macroquad::rand::RandGenerator;
+
+use crate::coins::CoinSpawner;
+use crate::difficulty::Difficulty;
+use crate::ghost::Ghost;
+use crate::powerups::{Pickup, PowerUps, PICKUP_RADIUS, POWER_UPS};
+
+// Virtual resolution, the game is drawn at this size and scaled to fit the window
+pub const SCREEN_WIDTH: f32 = 1700.0;
+pub const SCREEN_HEIGHT: f32 = 900.0;
+const GRAVITY: f32 = 0.25;
+const JUMP_STRENGTH: f32 = -5.0;
+const PIPE_SPEED: f32 = -3.0;
+const PIPE_SPACING: f32 = 550.0;
+const MIN_OBSTACLE_GAP: f32 = 250.0;  // obstacles never grow so tall the bird can't get past
+const PIPE_WIDTH: f32 = 80.0;
+const PIPE_GAP_HEIGHT: f32 = 320.0;  // gap between a pair of classic pipes at the start
+const MIN_PIPE_GAP: f32 = 180.0;  // the gap never closes further, the bird is about 40 high
+const MAX_CENTER_DELTA: f32 = 220.0;  // how far the gap moves up or down from one pair to the next
+const PIPE_MARGIN: f32 = 60.0;  // shortest a classic pipe gets
+const COIN_CLEARANCE: f32 = 40.0;  // coin patterns keep this far from the obstacles around them
+const HITBOX_INSET: f32 = 0.15;  // share of the sprite trimmed off each side, the corners are see-through
+const PICKUP_CHANCE: f32 = 0.2;  // share of the stretches between obstacles that get a power-up
+const MAGNET_PULL: f32 = 8.0;  // how fast the magnet drags coins towards the bird, on top of the scrolling
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum ObstacleMode {
+    Floating,  // single rectangles of random size anywhere
+    Classic,   // pairs of pipes from the top and bottom with a gap in between
+}
+
+impl ObstacleMode {
+    pub fn name(&self) -> &'static str {
+        match self {
+            ObstacleMode::Floating => "Floating blocks",
+            ObstacleMode::Classic => "Classic pipes",
+        }
+    }
+}
+
+#[derive(PartialEq)]
+pub enum PipeKind {
+    Floating,
+    Top,
+    Bottom,  // the bottom pipe of a pair gives the point for passing it
+}
+
+pub struct Pipe {
+    pub position: Vec2,  // This is the top-left corner of the rectangle
+    pub size: Vec2,      // Width and Height of the rectangle
+    pub kind: PipeKind,
+    pub passed: bool,    // set once the bird flew past it
+}
+
+fn create_random_pipe(rng: &RandGenerator, x: f32, size_scale: f32) -> Pipe {
+    let width = rng.gen_range(30.0, 100.0);   // Random width between 30 and 100 units
+    // Random height between 100 and 300 units, scaled up as the game gets harder
+    let height = (rng.gen_range(100.0, 300.0) * size_scale).min(SCREEN_HEIGHT - MIN_OBSTACLE_GAP);
+    let y_position = rng.gen_range(0.0, SCREEN_HEIGHT - height); // Ensuring the rectangle fits on the screen
+
+    Pipe {
+        position: Vec2::new(x, y_position),
+        size: Vec2::new(width, height),
+        kind: PipeKind::Floating,
+        passed: false,
+    }
+}
+
+// Top and bottom pipe around a gap centered at `gap_center`
+fn create_pipe_pair(x: f32, gap_center: f32, gap_height: f32) -> [Pipe; 2] {
+    let gap_top = gap_center - gap_height / 2.0;
+    let gap_bottom = gap_center + gap_height / 2.0;
+    [
+        Pipe {
+            position: Vec2::new(x, 0.0),
+            size: Vec2::new(PIPE_WIDTH, gap_top),
+            kind: PipeKind::Top,
+            passed: false,
+        },
+        Pipe {
+            position: Vec2::new(x, gap_bottom),
+            size: Vec2::new(PIPE_WIDTH, SCREEN_HEIGHT - gap_bottom),
+            kind: PipeKind::Bottom,
+            passed: false,
+        },
+    ]
+}
+
+fn is_circle_colliding_with_bird(bird: &Bird, circle_pos: Vec2, circle_radius: f32) -> bool {
+    let hitbox = bird.hitbox();
+    is_circle_colliding_with_rect(circle_pos, circle_radius, hitbox.point(), hitbox.size())
+}
+
+fn is_bird_colliding_with_pipe(bird: &Bird, pipe: &Pipe) -> bool {
+    bird.hitbox().overlaps(&Rect::new(pipe.position.x, pipe.position.y, pipe.size.x, pipe.size.y))
+}
+
+// Helper function to check if a circle is colliding with a rectangle
+pub(crate) fn is_circle_colliding_with_rect(circle_pos: Vec2, circle_radius: f32, rect_pos: Vec2, rect_size: Vec2) -> bool {
+    // Find the closest point in the rectangle to the circle
+    let closest_x = circle_pos.x.clamp(rect_pos.x, rect_pos.x + rect_size.x);
+    let closest_y = circle_pos.y.clamp(rect_pos.y, rect_pos.y + rect_size.y);
+
+    // Calculate the distance between the circle's center and the closest point
+    let distance_x = circle_pos.x - closest_x;
+    let distance_y = circle_pos.y - closest_y;
+
+    // If the distance is less than the circle's radius, an intersection occurs
+    (distance_x * distance_x + distance_y * distance_y) < (circle_radius * circle_radius)
+}
+
+
+fn update_pipes(pipes: &mut Vec<Pipe>, speed: f32) {
+    for pipe in pipes.iter_mut() {
+        pipe.position.x += speed;
+    }
+
+    pipes.retain(|pipe| pipe.position.x + pipe.size.x > 0.0);
+}
+
+
+pub struct Coin {
+    pub position: Vec2,
+    pub radius: f32,
+}
+
+pub struct Bird {
+    pub position: Vec2,  // center of the sprite
+    pub velocity: f32,
+    pub size: Vec2,      // size of the sprite as drawn
+}
+
+impl Bird {
+    // The drawn sprite, shrunk by HITBOX_INSET on every side
+    pub fn hitbox(&self) -> Rect {
+        let size = self.size * (1.0 - 2.0 * HITBOX_INSET);
+        Rect::new(self.position.x - size.x / 2.0, self.position.y - size.y / 2.0, size.x, size.y)
+    }
+}
+
+
+pub struct GameState {
+    pub bird: Bird,
+    pub pipes: Vec<Pipe>,
+    pub coins: Vec<Coin>,
+    pub score: i32,   // tracks the score, incremented when a coin is collected or a pair of pipes passed
+    pub difficulty: Difficulty,
+    pub obstacles: ObstacleMode,
+    gap_center: f32,  // center of the last classic gap, the next one stays close to it
+    seed: u64,  // obstacles and coins come from this, the same seed flies the same course
+    // Separate streams from the same seed, so what the player does with coins and
+    // pickups never changes the obstacles or the next pickup
+    obstacle_rng: RandGenerator,
+    coin_spawner: CoinSpawner,
+    pickup_rng: RandGenerator,
+    pub pickups: Vec<Pickup>,
+    pub power_ups: PowerUps,
+    pub frames: u32,  // frames played, the game advances a fixed step per frame
+    pub distance: f32,  // how far the world scrolled, drives the background
+    next_obstacle_at: f32,  // distance at which the next obstacle enters at the right edge
+    pub speed_multiplier: f32,
+    pub recording: Vec<Vec2>,  // this run so far, in the same format as a ghost
+    ghost: Option<Ghost>,  // best run on this seed, flown alongside
+}
+
+impl GameState {
+    // Races `ghost` on its course if there is one, otherwise flies a fresh one.
+    // `bird_size` is the bird as drawn, its hitbox is taken from that
+    pub fn new(difficulty: Difficulty, obstacles: ObstacleMode, ghost: Option<Ghost>, bird_size: Vec2) -> Self {
+        let seed = ghost.as_ref().map_or_else(new_seed, |ghost| ghost.seed);
+        Self {
+            ghost,
+            ..Self::with_seed(difficulty, obstacles, seed, bird_size)
+        }
+    }
+
+    // The course `seed` lays out, without a ghost
+    pub fn with_seed(difficulty: Difficulty, obstacles: ObstacleMode, seed: u64, bird_size: Vec2) -> Self {
+        let obstacle_rng = RandGenerator::new();
+        obstacle_rng.srand(seed);
+        let pickup_rng = RandGenerator::new();
+        pickup_rng.srand(seed.wrapping_add(2));
+        let bird = Bird {
+            position: vec2(SCREEN_WIDTH * 0.2, SCREEN_HEIGHT / 2.0),
+            velocity: 0.0,
+            size: bird_size,
+        };
+        let pipes = Vec::new();
+        let coins = Vec::new();
+        Self {
+            bird,
+            pipes,
+            coins,
+            score: 0,
+            difficulty,
+            obstacles,
+            gap_center: SCREEN_HEIGHT / 2.0,
+            seed,
+            obstacle_rng,
+            coin_spawner: CoinSpawner::new(seed.wrapping_add(1)),
+            pickup_rng,
+            pickups: Vec::new(),
+            power_ups: PowerUps::default(),
+            frames: 0,
+            distance: 0.0,
+            next_obstacle_at: 0.0,
+            speed_multiplier: 1.0,
+            recording: Vec::new(),
+            ghost: None,
+        }
+    }
+
+    // Ramps up with every coin and every second survived, see `Difficulty`
+    fn update_difficulty(&mut self) {
+        self.frames += 1;
+        self.speed_multiplier = self.difficulty.multiplier(self.score, self.frames as f32 / 60.0);
+    }
+
+    fn pipe_speed(&self) -> f32 {
+        PIPE_SPEED * self.difficulty.speed * self.speed_multiplier
+    }
+
+    // The course itself ramps up with distance only, not with coins or frames, so
+    // that a seed always lays out the same obstacles however it is flown
+    fn layout_multiplier(&self) -> f32 {
+        let seconds_at_start_speed = self.next_obstacle_at / (-PIPE_SPEED * self.difficulty.speed * 60.0);
+        self.difficulty.multiplier(0, seconds_at_start_speed)
+    }
+
+    fn pipe_spacing(&self) -> f32 {
+        PIPE_SPACING * self.difficulty.spacing / self.layout_multiplier()
+    }
+
+    fn obstacle_size(&self) -> f32 {
+        self.difficulty.obstacle_size * self.layout_multiplier()
+    }
+
+    // Shrinks as the game gets harder, down to MIN_PIPE_GAP
+    fn pipe_gap(&self) -> f32 {
+        (PIPE_GAP_HEIGHT / self.obstacle_size()).max(MIN_PIPE_GAP)
+    }
+
+    // Spawns the next obstacle at the right edge once the world scrolled far enough,
+    // plus coins in the stretch behind it
+    fn spawn_obstacle(&mut self) {
+        if self.distance < self.next_obstacle_at {
+            return;
+        }
+        let first_new = self.pipes.len();
+        // Placed where it belongs in the world, not wherever this frame's step ended
+        let x = SCREEN_WIDTH - (self.distance - self.next_obstacle_at);
+        let (width, gap) = match self.obstacles {
+            ObstacleMode::Floating => {
+                let pipe = create_random_pipe(&self.obstacle_rng, x, self.obstacle_size());
+                let width = pipe.size.x;
+                self.pipes.push(pipe);
+                (width, None)
+            }
+            ObstacleMode::Classic => {
+                // Bounded step from the last gap so it can always be reached in time
+                let gap = self.pipe_gap();
+                let limit = gap / 2.0 + PIPE_MARGIN;
+                self.gap_center = (self.gap_center + self.obstacle_rng.gen_range(-MAX_CENTER_DELTA, MAX_CENTER_DELTA))
+                    .clamp(limit, SCREEN_HEIGHT - limit);
+                self.pipes.extend(create_pipe_pair(x, self.gap_center, gap));
+                (PIPE_WIDTH, Some(Rect::new(x, self.gap_center - gap / 2.0, PIPE_WIDTH, gap)))
+            }
+        };
+
+        // The next obstacle comes a spacing later, coins fill the stretch up to it.
+        // Only the new obstacle and coins are in the way there, never anything the
+        // player already flew into
+        let spacing = self.pipe_spacing();
+        let from_x = x + width + COIN_CLEARANCE;
+        let to_x = x + spacing - COIN_CLEARANCE;
+        let obstacle = &self.pipes[first_new..];
+        let coins = self.coin_spawner.spawn(obstacle, from_x, to_x, SCREEN_HEIGHT, gap);
+        if let Some(pickup) = self.spawn_pickup(obstacle, &coins, from_x, to_x) {
+            self.pickups.push(pickup);
+        }
+        self.coins.extend(coins);
+        self.next_obstacle_at += spacing;
+    }
+
+    // Now and then a power-up in the stretch between `from_x` and `to_x`, dropped
+    // if it would touch a pipe or a coin
+    fn spawn_pickup(&self, pipes: &[Pipe], coins: &[Coin], from_x: f32, to_x: f32) -> Option<Pickup> {
+        if self.pickup_rng.gen_range(0.0, 1.0) >= PICKUP_CHANCE || to_x <= from_x {
+            return None;
+        }
+        let margin = PICKUP_RADIUS + PIPE_MARGIN;
+        let position = vec2(self.pickup_rng.gen_range(from_x, to_x), self.pickup_rng.gen_range(margin, SCREEN_HEIGHT - margin));
+        let power_up = &POWER_UPS[self.pickup_rng.gen_range(0, POWER_UPS.len())];
+        let blocked = pipes.iter().any(|pipe| is_circle_colliding_with_rect(position, PICKUP_RADIUS + COIN_CLEARANCE, pipe.position, pipe.size))
+            || coins.iter().any(|coin| coin.position.distance(position) < coin.radius + PICKUP_RADIUS);
+        (!blocked).then_some(Pickup { position, power_up })
+    }
+
+    // Where the ghost was after as many frames as this run has flown and how fast it
+    // was falling, None once its run ended
+    pub fn ghost_position(&self) -> Option<(Vec2, f32)> {
+        let ghost = self.ghost.as_ref()?;
+        let index = self.recording.len().saturating_sub(1);
+        let frame = ghost.frames.get(index)?;
+        let velocity = index.checked_sub(1).map_or(0.0, |previous| frame.y - ghost.frames[previous].y);
+        Some((vec2(self.bird.position.x + frame.x - self.distance, frame.y), velocity))
+    }
+
+    // Keeps this run as the ghost to race if it beat the stored one, true if it did
+    pub fn save_if_best(&self) -> bool {
+        let best = Ghost::load(&self.difficulty, self.obstacles).map_or(-1, |ghost| ghost.score);
+        if self.score <= best {
+            return false;
+        }
+        let ghost = Ghost {
+            seed: self.seed,
+            score: self.score,
+            frames: self.recording.clone(),
+        };
+        ghost.save(&self.difficulty, self.obstacles);
+        true
+    }
+
+    /// Advances the game by one frame, `flap` tells whether the bird flaps on it.
+    /// Reads no input, clock or screen, so the same game and flaps always play out
+    /// the same. Returns false once the bird crashed.
+    pub fn update(&mut self, flap: bool) -> bool {
+        self.update_difficulty();
+        self.power_ups.update();
+
+        // Bird physics
+        self.bird.velocity += GRAVITY;
+        self.bird.position.y += self.bird.velocity;
+
+        if flap {
+            self.bird.velocity = JUMP_STRENGTH;
+        }
+
+        // Spawn pipes at intervals, a spacing apart in the world
+        self.spawn_obstacle();
+
+        let speed = self.pipe_speed() * self.power_ups.time_scale();
+        update_pipes(&mut self.pipes, speed);
+        self.distance -= speed;
+
+        for coin in &mut self.coins {
+            coin.position.x += speed;
+        }
+        for pickup in &mut self.pickups {
+            pickup.position.x += speed;
+        }
+
+        let magnet_radius = self.power_ups.magnet_radius();
+        if magnet_radius > 0.0 {
+            for coin in &mut self.coins {
+                let to_bird = self.bird.position - coin.position;
+                if to_bird.length() < magnet_radius {
+                    coin.position += to_bird.normalize_or_zero() * MAGNET_PULL.min(to_bird.length());
+                }
+            }
+        }
+
+        // Flying past a pair of pipes is worth a point
+        let bird_left = self.bird.hitbox().left();
+        for pipe in self.pipes.iter_mut() {
+            if pipe.kind == PipeKind::Bottom && !pipe.passed && pipe.position.x + pipe.size.x < bird_left {
+                pipe.passed = true;
+                self.score += 1;
+            }
+        }
+
+        // Collision with ground or ceiling
+        let hitbox = self.bird.hitbox();
+        let mut crashed = hitbox.top() <= 0.0 || hitbox.bottom() >= SCREEN_HEIGHT;
+
+        // Collision with pipes, a shield takes the hit and breaks whatever the bird flew into
+        if self.pipes.iter().any(|pipe| is_bird_colliding_with_pipe(&self.bird, pipe)) {
+            if self.power_ups.absorb_hit() {
+                let bird = &self.bird;
+                self.pipes.retain(|pipe| !is_bird_colliding_with_pipe(bird, pipe));
+            } else {
+                crashed = true;
+            }
+        }
+
+        // collision with coins
+        self.coins.retain(|coin| {
+            let collided = is_circle_colliding_with_bird(&self.bird, coin.position, coin.radius);
+            if collided {
+                self.score += 1;
+            }
+            !collided && coin.position.x + coin.radius > 0.0
+        });
+
+        // Flying into a pickup starts its power-up
+        self.pickups.retain(|pickup| {
+            let collided = is_circle_colliding_with_bird(&self.bird, pickup.position, PICKUP_RADIUS);
+            if collided {
+                self.power_ups.collect(pickup.power_up);
+            }
+            !collided && pickup.position.x + PICKUP_RADIUS > 0.0
+        });
+
+        !crashed
+    }
+
+    /// What an AI pilot gets to see, see `Observation`.
+    pub fn observe(&self) -> Observation {
+        let hitbox = self.bird.hitbox();
+        // The next obstacle is the nearest one not yet fully behind the bird, a
+        // classic pair shares its x
+        let next_x = self
+            .pipes
+            .iter()
+            .filter(|pipe| pipe.position.x + pipe.size.x >= hitbox.left())
+            .map(|pipe| pipe.position.x)
+            .fold(f32::INFINITY, f32::min);
+        let (distance, (top, bottom)) = if next_x.is_finite() {
+            ((next_x - hitbox.right()) / SCREEN_WIDTH, self.opening_at(next_x))
+        } else {
+            (1.0, (0.0, SCREEN_HEIGHT))
+        };
+        Observation {
+            height: self.bird.position.y / SCREEN_HEIGHT,
+            velocity: self.bird.velocity / -JUMP_STRENGTH,
+            distance,
+            opening_top: top / SCREEN_HEIGHT,
+            opening_bottom: bottom / SCREEN_HEIGHT,
+        }
+    }
+
+    // Widest free span, top and bottom, between the obstacles that start at `x`
+    fn opening_at(&self, x: f32) -> (f32, f32) {
+        let mut solid: Vec<(f32, f32)> = self
+            .pipes
+            .iter()
+            .filter(|pipe| pipe.position.x == x)
+            .map(|pipe| (pipe.position.y, pipe.position.y + pipe.size.y))
+            .collect();
+        solid.sort_by(|a, b| a.0.total_cmp(&b.0));
+        solid.push((SCREEN_HEIGHT, SCREEN_HEIGHT));
+
+        let mut widest = (0.0, 0.0);
+        let mut free_from: f32 = 0.0;
+        for (top, bottom) in solid {
+            if top - free_from > widest.1 - widest.0 {
+                widest = (free_from, top);
+            }
+            free_from = free_from.max(bottom);
+        }
+        widest
+    }
+}
+
+/// The game as an AI pilot sees it, scaled so every value stays around 0 to 1.
+pub struct Observation {
+    pub height: f32,          // bird center, 0 at the top and 1 at the bottom
+    pub velocity: f32,        // in flaps, positive is falling
+    pub distance: f32,        // from the bird's front to the next obstacle, in screen widths
+    pub opening_top: f32,     // the free span at the next obstacle, a classic gap or the
+    pub opening_bottom: f32,  // larger side of a floating block, in screen heights
+}
+
+// Seed for a new game's obstacles and coins
+pub fn new_seed() -> u64 {
+    (miniquad::date::now() * 1000.0) as u64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::difficulty::PRESETS;
+
+    // A 32 pixel cell of bird.png drawn one and a half times as big, like the game does
+    const BIRD_SIZE: Vec2 = vec2(48.0, 48.0);
+
+    // Obstacles as (where in the world, top, width, height) and pickups as (height,
+    // name) in the order they spawned
+    type Course = (Vec<(f32, f32, f32, f32)>, Vec<(f32, &'static str)>);
+
+    // The course flown with the bird held in the middle of the screen until the
+    // world scrolled `distance`
+    fn course(mut game: GameState, distance: f32, every_frame: impl Fn(&mut GameState)) -> Course {
+        let mut obstacles = Vec::new();
+        let mut pickups = Vec::new();
+        while game.distance < distance {
+            every_frame(&mut game);
+            game.bird.position.y = SCREEN_HEIGHT / 2.0;
+            game.bird.velocity = 0.0;
+            let spawned_at = game.next_obstacle_at;
+            game.update(false);
+            if game.next_obstacle_at != spawned_at {
+                let newest = game.pipes.last().unwrap().position.x;
+                for pipe in game.pipes.iter().filter(|pipe| pipe.position.x == newest) {
+                    obstacles.push((spawned_at, pipe.position.y, pipe.size.x, pipe.size.y));
+                }
+                for pickup in game.pickups.iter().filter(|pickup| pickup.position.x > newest) {
+                    pickups.push((pickup.position.y, pickup.power_up.name));
+                }
+            }
+        }
+        (obstacles, pickups)
+    }
+
+    fn game() -> GameState {
+        GameState::with_seed(PRESETS[1], ObstacleMode::Classic, 1, BIRD_SIZE)
+    }
+
+    #[test]
+    fn flying_into_a_pipe_ends_the_run() {
+        let mut game = game();
+        let hitbox = game.bird.hitbox();
+        game.pipes.push(Pipe {
+            position: hitbox.point(),
+            size: hitbox.size(),
+            kind: PipeKind::Floating,
+            passed: false,
+        });
+        assert!(!game.update(false));
+    }
+
+    #[test]
+    fn flying_through_a_gap_keeps_going() {
+        let mut game = game();
+        let x = game.bird.hitbox().left();
+        game.pipes.extend(create_pipe_pair(x, game.bird.position.y, PIPE_GAP_HEIGHT));
+        assert!(game.update(false));
+    }
+
+    #[test]
+    fn passing_a_pair_of_pipes_scores_once() {
+        let mut game = game();
+        // Just behind the bird, the next step scrolls it past
+        let x = game.bird.hitbox().left() - PIPE_WIDTH;
+        game.pipes.extend(create_pipe_pair(x, game.bird.position.y, PIPE_GAP_HEIGHT));
+        assert!(game.update(false));
+        assert_eq!(game.score, 1);
+        assert!(game.update(false));
+        assert_eq!(game.score, 1);
+    }
+
+    #[test]
+    fn same_seed_spawns_the_same_course() {
+        for obstacles in [ObstacleMode::Floating, ObstacleMode::Classic] {
+            let mut a = GameState::with_seed(PRESETS[2], obstacles, 99, BIRD_SIZE);
+            let mut b = GameState::with_seed(PRESETS[2], obstacles, 99, BIRD_SIZE);
+            for frame in 0..3000 {
+                let flap = frame % 25 == 0;
+                assert_eq!(a.update(flap), b.update(flap));
+                let pipes = |game: &GameState| game.pipes.iter().map(|pipe| (pipe.position, pipe.size)).collect::<Vec<_>>();
+                let coins = |game: &GameState| game.coins.iter().map(|coin| coin.position).collect::<Vec<_>>();
+                let pickups = |game: &GameState| game.pickups.iter().map(|pickup| (pickup.position, pickup.power_up.name)).collect::<Vec<_>>();
+                assert_eq!(pipes(&a), pipes(&b));
+                assert_eq!(coins(&a), coins(&b));
+                assert_eq!(pickups(&a), pickups(&b));
+            }
+            assert!(a.frames == 3000 && !a.pipes.is_empty());
+        }
+    }
+
+    #[test]
+    fn course_does_not_depend_on_what_the_player_collects() {
+        for obstacles in [ObstacleMode::Floating, ObstacleMode::Classic] {
+            let untouched = course(GameState::with_seed(PRESETS[1], obstacles, 3, BIRD_SIZE), 20000.0, |_| {});
+            // Every power-up all the time: the shield breaks pipes, the magnet pulls
+            // coins in and slow time changes how far each frame scrolls
+            let powered = course(GameState::with_seed(PRESETS[1], obstacles, 3, BIRD_SIZE), 20000.0, |game| {
+                for power_up in POWER_UPS {
+                    game.power_ups.collect(power_up);
+                }
+            });
+            assert!(!untouched.0.is_empty() && !untouched.1.is_empty());
+            assert_eq!(untouched, powered);
+        }
+    }
+}
@@ -0,0 +1,57 @@
+use macroquad::prelude::*;
+
+use crate::difficulty::Difficulty;
+use crate::game::ObstacleMode;
+
+/// A finished run that can be flown again: the seed its obstacles and coins came
+/// from, and where the bird was on every frame as (distance scrolled, height).
+/// Stored as a "seed score" line followed by one "distance height" line per frame.
+pub struct Ghost {
+    pub seed: u64,
+    pub score: i32,
+    pub frames: Vec<Vec2>,
+}
+
+// Every difficulty and obstacle mode keeps its own best run
+fn key(difficulty: &Difficulty, obstacles: ObstacleMode) -> String {
+    let obstacles = match obstacles {
+        ObstacleMode::Floating => "floating",
+        ObstacleMode::Classic => "classic",
+    };
+    format!("flappybird_ghost_{}_{}", difficulty.name.to_lowercase(), obstacles)
+}
+
+impl Ghost {
+    pub fn load(difficulty: &Difficulty, obstacles: ObstacleMode) -> Option<Ghost> {
+        Self::parse(&storage::load(&key(difficulty, obstacles))?)
+    }
+
+    pub fn save(&self, difficulty: &Difficulty, obstacles: ObstacleMode) {
+        storage::save(&key(difficulty, obstacles), &self.serialize());
+    }
+
+    // A broken save is dropped as a whole, half a ghost would fly into the pipes
+    fn parse(data: &str) -> Option<Ghost> {
+        let mut lines = data.lines();
+        let (seed, score) = lines.next()?.split_once(' ')?;
+        let frames = lines
+            .map(|line| {
+                let (distance, height) = line.split_once(' ')?;
+                Some(vec2(distance.parse().ok()?, height.parse().ok()?))
+            })
+            .collect::<Option<Vec<Vec2>>>()?;
+        Some(Ghost {
+            seed: seed.parse().ok()?,
+            score: score.parse().ok()?,
+            frames,
+        })
+    }
+
+    fn serialize(&self) -> String {
+        let mut data = format!("{} {}\n", self.seed, self.score);
+        for frame in &self.frames {
+            data.push_str(&format!("{:.1} {:.1}\n", frame.x, frame.y));
+        }
+        data
+    }
+}
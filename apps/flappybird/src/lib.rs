@@ -0,0 +1,9 @@
+//! Flappy bird without a window, shared by the game in main.rs and the headless
+//! trainer in bin/train.rs.
+
+pub mod brain;
+pub mod coins;
+pub mod difficulty;
+pub mod game;
+pub mod ghost;
+pub mod powerups;
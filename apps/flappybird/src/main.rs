@@ -1,37 +1,69 @@
 use macroquad::prelude::*;
+use myapp::brain::Brain;
+use myapp::difficulty::{Difficulty, PRESETS};
+use myapp::game::{GameState, ObstacleMode, Pipe, PipeKind, SCREEN_HEIGHT, SCREEN_WIDTH};
+use myapp::ghost::Ghost;
+use myapp::powerups::{Pickup, PICKUP_RADIUS};
+use parallax::{LayerConfig, Parallax, Source};
+
+mod animation;
+mod canvas;
+use animation::{Animation, AnimationFrame, Animations, VelocityRotator};
+use canvas::VirtualCanvas;
 
-const SCREEN_WIDTH: f32 = 1700.0;
-const SCREEN_HEIGHT: f32 = 900.0;
-const GRAVITY: f32 = 0.25;
-const JUMP_STRENGTH: f32 = -5.0;
-const PIPE_SPEED: f32 = -3.0;
-const PIPE_SPACING: f32 = 550.0;
 const FRAME_THICKNESS: f32 = 5.0;
-const COIN_SPAWN_RATE: f32 = 0.02;  // for instance, 2% chance every frame
-
-const PIPE_WIDTH: f32 = 60.0;
-const PIPE_GAP_HEIGHT: f32 = 200.0;
-const PIPE_HEIGHT: f32 = SCREEN_HEIGHT;  // Pipes span from top to bottom of screen
-
-// struct Pipe {
-//     position: Vec2,  // This represents the x position and the y position of the bottom pipe's top edge
-//     gap_start: f32,  // This denotes the y position where the gap starts
-// }
-struct Pipe {
-    position: Vec2,  // This is the top-left corner of the rectangle
-    size: Vec2,      // Width and Height of the rectangle
-}
-
-fn create_random_pipe(screen_width: f32) -> Pipe {
-    let width = rand::gen_range(30.0, 100.0);   // Random width between 30 and 100 units
-    let height = rand::gen_range(100.0, 300.0);  // Random height between 50 and 300 units
-    let y_position = rand::gen_range(0.0, SCREEN_HEIGHT - height); // Ensuring the rectangle fits on the screen
-
-    Pipe {
-        position: Vec2::new(screen_width, y_position),
-        size: Vec2::new(width, height),
-    }
-}
+// Back to front: the sky crawls, the hills follow slowly, the ground keeps up with the pipes
+const BACKGROUND_LAYERS: &[LayerConfig] = &[
+    LayerConfig {
+        source: Source::Texture("background.png"),
+        scroll: 0.1,
+        y: 0.0,
+        height: SCREEN_HEIGHT,
+        tile_width: SCREEN_HEIGHT * 900.0 / 504.0,  // keeps the image's aspect ratio
+        mirror: true,
+    },
+    LayerConfig {
+        source: Source::Hills { color: Color::new(0.35, 0.55, 0.45, 1.0), base: 0.45, waves: &[(2, 60.0, 2.0), (5, 20.0, 5.0)] },
+        scroll: 0.3,
+        y: SCREEN_HEIGHT - 300.0,
+        height: 300.0,
+        tile_width: 1200.0,
+        mirror: false,
+    },
+    LayerConfig {
+        source: Source::Hills { color: Color::new(0.45, 0.35, 0.25, 1.0), base: 0.5, waves: &[(8, 6.0, 1.7), (13, 3.0, 0.4)] },
+        scroll: 1.0,
+        y: SCREEN_HEIGHT - 40.0,
+        height: 40.0,
+        tile_width: 800.0,
+        mirror: false,
+    },
+];
+const RESUME_COUNTDOWN: f64 = 3.0;  // seconds to get ready after starting or unpausing
+const TEXT_COLOR: Color = Color::new(42.0 / 255.0, 37.0 / 255.0, 56.0 / 255.0, 1.0);
+const BIRD_SCALE: f32 = 1.5;  // bird.png's cells are drawn one and a half times as big
+const GHOST_COLOR: Color = Color::new(1.0, 1.0, 1.0, 0.4);
+// Same as the Bevy port: flapping while climbing, gliding while falling
+const BIRD_ANIMATIONS: &[Animation] = &[
+    Animation {
+        frames: &[
+            AnimationFrame { index: 0, time: 0.1 },
+            AnimationFrame { index: 1, time: 0.1 },
+            AnimationFrame { index: 2, time: 0.3 },
+            AnimationFrame { index: 1, time: 0.1 },
+        ],
+    },
+    Animation {
+        frames: &[AnimationFrame { index: 3, time: 0.2 }],
+    },
+];
+const FLAPPING: usize = 0;
+const GLIDING: usize = 1;
+const BIRD_ROTATOR: VelocityRotator = VelocityRotator {
+    angle_up: std::f32::consts::PI * 0.5 * 0.7,
+    angle_down: -std::f32::consts::PI * 0.5 * 0.5,
+    velocity_max: 5.0,  // a flap
+};
 
 fn draw_game_frame() {
     // Top line
@@ -47,273 +79,373 @@ fn draw_game_frame() {
     draw_line(SCREEN_WIDTH, 0.0, SCREEN_WIDTH, SCREEN_HEIGHT, FRAME_THICKNESS, BLACK);
 }
 
-fn is_circle_colliding_with_bird(bird: &Bird, circle_pos: Vec2, circle_radius: f32) -> bool {
-    bird.position.distance(circle_pos) < bird.radius + circle_radius
+enum GameMode {
+    Title,
+    Playing,
+    Paused,
+    Resuming(f64),  // counting down until this time, then playing again
+    GameOver,
 }
 
-// fn is_bird_colliding_with_pipe(bird: &Bird, pipe: &Pipe) -> bool {
-//     // Check collision with bottom pipe
-//     if bird.position.x + bird.radius > pipe.position.x &&
-//        bird.position.x - bird.radius < pipe.position.x + PIPE_WIDTH &&
-//        bird.position.y + bird.radius > pipe.gap_start + PIPE_GAP_HEIGHT {
-//            return true;
-//     }
+// Space, up, a click or a tap, clicks and taps on the letterbox bars don't count
+fn is_flap_pressed(canvas: &VirtualCanvas) -> bool {
+    let clicked = is_mouse_button_pressed(MouseButton::Left) && canvas.mouse_position().is_some();
+    let tapped = touches()
+        .iter()
+        .any(|touch| touch.phase == TouchPhase::Started && canvas.to_game(touch.position).is_some());
+    clicked || tapped || is_key_pressed(KeyCode::Space) || is_key_pressed(KeyCode::Up)
+}
 
-//     // Check collision with top pipe
-//     if bird.position.x + bird.radius > pipe.position.x &&
-//        bird.position.x - bird.radius < pipe.position.x + PIPE_WIDTH &&
-//        bird.position.y - bird.radius < pipe.gap_start {
-//            return true;
-//     }
+fn is_pause_pressed() -> bool {
+    is_key_pressed(KeyCode::Escape) || is_key_pressed(KeyCode::P)
+}
 
-//     return false;
-// }
+struct Textures {
+    background: Parallax,
+    bird: Texture2D,
+    pipe_body: Texture2D,
+    pipe_head: Texture2D,
+}
 
+// Top-left corner of the start screen button for preset `index`
+const BUTTON_SIZE: Vec2 = vec2(300.0, 80.0);
+fn difficulty_button(index: usize) -> Vec2 {
+    vec2(SCREEN_WIDTH / 2.0 + (index as f32 - 1.0) * 360.0 - BUTTON_SIZE.x / 2.0, SCREEN_HEIGHT / 2.0)
+}
 
-fn is_bird_colliding_with_pipe(bird: &Bird, pipe: &Pipe) -> bool {
-    // Using a helper function to check if the circle collides with the rectangle
-    is_circle_colliding_with_rect(bird.position, bird.radius, pipe.position, pipe.size)
+// Preset picked by clicking its button or pressing its number
+fn pick_difficulty(canvas: &VirtualCanvas) -> Option<Difficulty> {
+    let keys = [KeyCode::Key1, KeyCode::Key2, KeyCode::Key3];
+    PRESETS.iter().enumerate().find_map(|(i, preset)| {
+        let button = Rect::new(difficulty_button(i).x, difficulty_button(i).y, BUTTON_SIZE.x, BUTTON_SIZE.y);
+        (is_button_clicked(canvas, button) || is_key_pressed(keys[i])).then_some(*preset)
+    })
 }
 
-// Helper function to check if a circle is colliding with a rectangle
-fn is_circle_colliding_with_rect(circle_pos: Vec2, circle_radius: f32, rect_pos: Vec2, rect_size: Vec2) -> bool {
-    // Find the closest point in the rectangle to the circle
-    let closest_x = circle_pos.x.clamp(rect_pos.x, rect_pos.x + rect_size.x);
-    let closest_y = circle_pos.y.clamp(rect_pos.y, rect_pos.y + rect_size.y);
+fn obstacle_button() -> Rect {
+    Rect::new(SCREEN_WIDTH / 2.0 - 250.0, SCREEN_HEIGHT / 2.0 + 130.0, 500.0, BUTTON_SIZE.y)
+}
 
-    // Calculate the distance between the circle's center and the closest point
-    let distance_x = circle_pos.x - closest_x;
-    let distance_y = circle_pos.y - closest_y;
+fn ghost_button() -> Rect {
+    Rect::new(SCREEN_WIDTH / 2.0 - 250.0, SCREEN_HEIGHT / 2.0 + 230.0, 500.0, BUTTON_SIZE.y)
+}
 
-    // If the distance is less than the circle's radius, an intersection occurs
-    (distance_x * distance_x + distance_y * distance_y) < (circle_radius * circle_radius)
+fn ai_button() -> Rect {
+    Rect::new(SCREEN_WIDTH / 2.0 - 250.0, SCREEN_HEIGHT / 2.0 + 330.0, 500.0, BUTTON_SIZE.y)
 }
 
+fn is_button_clicked(canvas: &VirtualCanvas, button: Rect) -> bool {
+    is_mouse_button_pressed(MouseButton::Left) && canvas.mouse_position().is_some_and(|pos| button.contains(pos))
+}
 
-fn update_pipes(pipes: &mut Vec<Pipe>) {
-    for pipe in pipes.iter_mut() {
-        pipe.position.x += PIPE_SPEED;
-    }
+fn draw_button(rect: Rect, label: &str) {
+    draw_rectangle(rect.x, rect.y, rect.w, rect.h, WHITE);
+    draw_rectangle_lines(rect.x, rect.y, rect.w, rect.h, FRAME_THICKNESS, TEXT_COLOR);
+    let size = measure_text(label, None, 40, 1.0);
+    draw_text(label, rect.x + (rect.w - size.width) / 2.0, rect.y + rect.h / 2.0 + 12.0, 40.0, TEXT_COLOR);
+}
 
-    pipes.retain(|pipe| pipe.position.x + pipe.size.x > 0.0);
+fn draw_pipe(pipe: &Pipe, body_texture: &Texture2D, head_texture: &Texture2D) {
+    let head_height = pipe.size.x * head_texture.height() / head_texture.width();
+    let (body_y, head_y) = match pipe.kind {
+        PipeKind::Floating => {
+            let color = Color::from_rgba(42, 37, 56, 255);
+            draw_rectangle(pipe.position.x, pipe.position.y, pipe.size.x, pipe.size.y, color);
+            return;
+        }
+        // The head sits at the end facing the gap
+        PipeKind::Top => (pipe.position.y, pipe.position.y + pipe.size.y - head_height),
+        PipeKind::Bottom => (pipe.position.y + head_height, pipe.position.y),
+    };
+    draw_texture_ex(body_texture, pipe.position.x, body_y, WHITE,
+        DrawTextureParams {
+            dest_size: Some(vec2(pipe.size.x, pipe.size.y - head_height)),
+            flip_y: pipe.kind == PipeKind::Top,
+            ..Default::default()
+        });
+    draw_texture_ex(head_texture, pipe.position.x, head_y, WHITE,
+        DrawTextureParams {
+            dest_size: Some(vec2(pipe.size.x, head_height)),
+            ..Default::default()
+        });
 }
 
+// Debug overlay, what the collision checks actually see
+fn draw_hitboxes(game_state: &GameState) {
+    let hitbox = game_state.bird.hitbox();
+    draw_rectangle_lines(hitbox.x, hitbox.y, hitbox.w, hitbox.h, 2.0, RED);
+    for pipe in &game_state.pipes {
+        draw_rectangle_lines(pipe.position.x, pipe.position.y, pipe.size.x, pipe.size.y, 2.0, MAGENTA);
+    }
+    for coin in &game_state.coins {
+        draw_circle_lines(coin.position.x, coin.position.y, coin.radius, 2.0, MAGENTA);
+    }
+    for pickup in &game_state.pickups {
+        draw_circle_lines(pickup.position.x, pickup.position.y, PICKUP_RADIUS, 2.0, MAGENTA);
+    }
+}
 
-struct Coin {
-    position: Vec2,
-    radius: f32,
+fn draw_pickup(pickup: &Pickup) {
+    let color = pickup.power_up.color;
+    draw_circle(pickup.position.x, pickup.position.y, PICKUP_RADIUS, Color::new(1.0, 1.0, 1.0, 0.8));
+    draw_circle_lines(pickup.position.x, pickup.position.y, PICKUP_RADIUS, 3.0, color);
+    let size = measure_text(pickup.power_up.letter, None, 30, 1.0);
+    draw_text(pickup.power_up.letter, pickup.position.x - size.width / 2.0, pickup.position.y + size.height / 2.0, 30.0, color);
 }
 
-struct Bird {
-    position: Vec2,
-    velocity: f32,
-    radius: f32,
-    // texture: Texture2D, // This is the new field for the image texture
+// One frame of the bird sheet centered at `position`, tilted by how fast it falls
+fn draw_bird_sprite(texture: &Texture2D, position: Vec2, size: Vec2, velocity: f32, source: Rect, color: Color) {
+    let corner = position - size / 2.0;
+    draw_texture_ex(texture, corner.x, corner.y, color,
+        DrawTextureParams {
+            dest_size: Some(size),
+            source: Some(source),
+            rotation: BIRD_ROTATOR.rotation(velocity),
+            ..Default::default()  // use default values for other parameters
+        });
 }
 
+// Behind the bird: a trail while time is slowed, the reach of the magnet
+fn draw_power_up_trails(game_state: &GameState, textures: &Textures, animation: &Animations) {
+    let power_ups = &game_state.power_ups;
+    let bird = &game_state.bird;
+    if power_ups.time_scale() < 1.0 {
+        for i in (1..=3).rev() {
+            let position = bird.position - vec2(i as f32 * 20.0, 0.0);
+            let color = Color::new(0.6, 0.2, 0.9, 0.4 - i as f32 * 0.1);
+            draw_bird_sprite(&textures.bird, position, bird.size, bird.velocity, animation.source(), color);
+        }
+    }
+    if power_ups.magnet_radius() > 0.0 {
+        // A ring closing in on the bird, over and over
+        let pull = (game_state.frames % 40) as f32 / 40.0;
+        let radius = power_ups.magnet_radius() * (1.0 - pull);
+        draw_circle_lines(bird.position.x, bird.position.y, radius, 2.0, Color::new(0.9, 0.16, 0.22, pull * 0.6));
+    }
+}
 
-// impl Pipe {
-//     fn new(x: f32) -> Self {
-//         let gap_y = rand::gen_range(SCREEN_HEIGHT * 0.3, SCREEN_HEIGHT * 0.7);
-//         Pipe {
-//             position: vec2(x, 0.0),
-//             gap_y,
-//         }
-//     }
-// }
+// On top of the bird: the shield bubble
+fn draw_shield(game_state: &GameState) {
+    if game_state.power_ups.shield() {
+        let bird = &game_state.bird;
+        let radius = bird.size.x * 0.6;
+        draw_circle(bird.position.x, bird.position.y, radius, Color::new(0.4, 0.75, 1.0, 0.25));
+        draw_circle_lines(bird.position.x, bird.position.y, radius, 3.0, SKYBLUE);
+    }
+}
 
+// Name and a bar with the time left for each active power-up, under the score
+fn draw_power_up_timers(game_state: &GameState) {
+    for (i, (power_up, left)) in game_state.power_ups.timers().enumerate() {
+        let y = 100.0 + i as f32 * 30.0;
+        draw_text(power_up.name, 10.0, y, 30.0, power_up.color);
+        draw_rectangle(150.0, y - 16.0, 150.0 * left, 14.0, power_up.color);
+        draw_rectangle_lines(150.0, y - 16.0, 150.0, 14.0, 2.0, WHITE);
+    }
+}
 
-enum GameMode {
-    Playing,
-    GameOver,
+fn draw_centered_text(text: &str, y: f32, font_size: f32) {
+    let size = measure_text(text, None, font_size as u16, 1.0);
+    draw_text(text, (SCREEN_WIDTH - size.width) / 2.0, y, font_size, TEXT_COLOR);
 }
 
-struct GameState {
-    bird: Bird,
-    pipes: Vec<Pipe>,
-    coins: Vec<Coin>,
-    score: i32,   // tracks the score, incremented when a coin is collected
-    speed_multiplier: f32,
+// `ai_plays` is None when there is no trained brain to play with
+fn draw_title_screen(obstacles: ObstacleMode, race_ghost: bool, ai_plays: Option<bool>) {
+    clear_background(Color::new(63.0 / 255.0, 145.0 / 255.0, 195.0 / 255.0, 1.0));
+    draw_game_frame();
+    draw_centered_text("Flappy Bird", SCREEN_HEIGHT / 5.0, 80.0);
+    draw_centered_text("Flap with [Space], [Up], a click or a tap", SCREEN_HEIGHT / 5.0 + 60.0, 36.0);
+    draw_centered_text("Fly through the gaps and grab the coins, [Esc] or [P] pauses", SCREEN_HEIGHT / 5.0 + 100.0, 36.0);
+    draw_centered_text("Power-ups: (S)hield takes a hit, (M)agnet pulls coins in, slow (T)ime", SCREEN_HEIGHT / 5.0 + 140.0, 36.0);
+    draw_centered_text("Pick a difficulty to start", SCREEN_HEIGHT / 2.0 - 30.0, 40.0);
+    for (i, preset) in PRESETS.iter().enumerate() {
+        let pos = difficulty_button(i);
+        draw_button(Rect::new(pos.x, pos.y, BUTTON_SIZE.x, BUTTON_SIZE.y), &format!("[{}] {}", i + 1, preset.name));
+    }
+    draw_button(obstacle_button(), &format!("[M] {}", obstacles.name()));
+    draw_button(ghost_button(), if race_ghost { "[G] Race your best: on" } else { "[G] Race your best: off" });
+    let ai_label = match ai_plays {
+        Some(true) => "[A] AI plays: on",
+        Some(false) => "[A] AI plays: off",
+        None => "AI plays: no brain.txt",
+    };
+    draw_button(ai_button(), ai_label);
 }
 
-impl GameState {
-    fn new() -> Self {
-        // let bird = Bird::new();
-        let bird = Bird {
-            position: vec2(SCREEN_WIDTH * 0.2, SCREEN_HEIGHT / 2.0),
-            velocity: 0.0,
-            radius: 40.0,
-
-        };
-        // let pipes = vec![Pipe::new(SCREEN_WIDTH)];
-        let pipes = Vec::new();
-        let coins = Vec::new();
-        // GameState { bird, pipes, coins }
-        Self {
-            bird: bird,
-            pipes: pipes,
-            coins: coins,
-            score: 0,
-            speed_multiplier: 1.0
-            // game_over: false,
-            // show_new_game_screen: true,
-        }
+fn draw_game(game_state: &GameState, textures: &Textures, animation: &Animations, show_hitboxes: bool) {
+    textures.background.draw(game_state.distance, SCREEN_WIDTH);
+    draw_game_frame();
+
+    // The best run, a step ahead or behind depending on how far each got
+    if let Some((ghost, velocity)) = game_state.ghost_position() {
+        // Holds the first frame of whatever it would be playing
+        let pose = BIRD_ANIMATIONS[if velocity < 0.0 { FLAPPING } else { GLIDING }].frames[0].index;
+        draw_bird_sprite(&textures.bird, ghost, game_state.bird.size, velocity, animation.cell(pose), GHOST_COLOR);
     }
 
-    // fn restart(&mut self) {
-    //     *self = GameState::new();
-    // }
+    draw_power_up_trails(game_state, textures, animation);
+
+    // Draw bird
+    draw_bird_sprite(&textures.bird, game_state.bird.position, game_state.bird.size, game_state.bird.velocity, animation.source(), WHITE);
+    draw_shield(game_state);
+
+    // Draw Coins
+    for coin in &game_state.coins {
+        draw_circle(coin.position.x, coin.position.y, coin.radius, GOLD);
+    }
+    for pickup in &game_state.pickups {
+        draw_pickup(pickup);
+    }
+    // Draw pipes
+    for pipe in &game_state.pipes {
+        draw_pipe(pipe, &textures.pipe_body, &textures.pipe_head);
+    }
+
+    if show_hitboxes {
+        draw_hitboxes(game_state);
+    }
+
+    // draw score
+    let score_text = format!("Score: {}", game_state.score);
+    draw_text(&score_text, 10.0, 30.0, 40.0, WHITE);
+    let speed_text = format!("{} x{:.1}", game_state.difficulty.name, game_state.speed_multiplier);
+    draw_text(&speed_text, 10.0, 60.0, 30.0, WHITE);
+    draw_power_up_timers(game_state);
 }
 
-// ... [Bird and Pipe structures as before] ...
+// Dims the frozen game and puts a message on top
+fn draw_overlay(title: &str, hint: &str) {
+    draw_rectangle(0.0, 0.0, SCREEN_WIDTH, SCREEN_HEIGHT, Color::new(1.0, 1.0, 1.0, 0.5));
+    draw_centered_text(title, SCREEN_HEIGHT / 2.0, 80.0);
+    draw_centered_text(hint, SCREEN_HEIGHT / 2.0 + 50.0, 36.0);
+}
 
 #[macroquad::main("Flappy Bird")]
 async fn main() {
-    let background_texture = load_texture("background.png").await.unwrap();
-    let mut game_state = GameState::new();
-    let mut mode = GameMode::Playing;
-    let player_texture = load_texture("paper.png").await.unwrap();
-    let player_scale_factor = 0.2;
+    let textures = Textures {
+        background: Parallax::load(BACKGROUND_LAYERS).await.unwrap(),
+        bird: load_texture("bird.png").await.unwrap(),
+        pipe_body: load_texture("pipe_up.png").await.unwrap(),
+        pipe_head: load_texture("pipe_head.png").await.unwrap(),
+    };
+    textures.pipe_body.set_filter(FilterMode::Nearest);
+    textures.pipe_head.set_filter(FilterMode::Nearest);
+    textures.bird.set_filter(FilterMode::Nearest);
+    let mut bird_animation = Animations::new(BIRD_ANIMATIONS, &textures.bird, 2, 2);
+    let bird_size = textures.bird.size() / 2.0 * BIRD_SCALE;  // one cell of the 2x2 sheet
+    // Written by `cargo run --release --bin train`, the AI mode stays off without it
+    let brain = load_string("brain.txt").await.ok().and_then(|data| Brain::parse(&data));
+    let mut obstacles = ObstacleMode::Floating;
+    let mut race_ghost = true;
+    let mut ai_plays = false;
+    let mut game_state = GameState::new(PRESETS[1], obstacles, None, bird_size);
+    let mut mode = GameMode::Title;
+    let canvas = VirtualCanvas::new(SCREEN_WIDTH, SCREEN_HEIGHT);
+    let mut show_hitboxes = false;
+    let mut new_best = false;
 
     loop {
-        match mode {
-            GameMode::Playing => {
-                // Bird physics
-                game_state.bird.velocity += GRAVITY;
-                game_state.bird.position.y += game_state.bird.velocity;
-
-                if is_mouse_button_pressed(MouseButton::Left) {
-                    game_state.bird.velocity = JUMP_STRENGTH;
-                }
+        canvas.begin();
 
+        // F3 toggles the hitbox overlay
+        if is_key_pressed(KeyCode::F3) {
+            show_hitboxes = !show_hitboxes;
+        }
 
-                // Spawn pipes at intervals (for this example, we'll spawn a pipe every few frames for simplicity)
-                if game_state.pipes.is_empty() || game_state.pipes.last().unwrap().position.x <= SCREEN_WIDTH - PIPE_SPACING {
-                    game_state.pipes.push(create_random_pipe(SCREEN_WIDTH));
+        match mode {
+            GameMode::Title => {
+                draw_title_screen(obstacles, race_ghost, brain.is_some().then_some(ai_plays));
+
+                if is_button_clicked(&canvas, obstacle_button()) || is_key_pressed(KeyCode::M) {
+                    obstacles = match obstacles {
+                        ObstacleMode::Floating => ObstacleMode::Classic,
+                        ObstacleMode::Classic => ObstacleMode::Floating,
+                    };
                 }
-                // Pipe logic
-                // if game_state.pipes.is_empty() || game_state.pipes.last().unwrap().position.x < SCREEN_WIDTH - PIPE_SPACING {
-                //     let num_new_pipes = ((SCREEN_WIDTH - game_state.pipes.last().unwrap().position.x) / PIPE_SPACING) as usize;
-                //     for _ in 0..num_new_pipes {
-                //         let new_pipe_x = if game_state.pipes.is_empty() {
-                //             SCREEN_WIDTH
-                //         } else {
-                //             game_state.pipes.last().unwrap().position.x + PIPE_SPACING
-                //         };
-                //         game_state.pipes.push(Pipe::new(new_pipe_x));
-                //     }
-                // }
-
-                // periodically add coins:
-                if rand::gen_range(0.0, 1.0) < COIN_SPAWN_RATE {
-                    game_state.coins.push(Coin {
-                        position: Vec2::new(SCREEN_WIDTH, rand::gen_range(100.0, SCREEN_HEIGHT - 100.0)),
-                        radius: 15.0,
-                    });
+                if is_button_clicked(&canvas, ghost_button()) || is_key_pressed(KeyCode::G) {
+                    race_ghost = !race_ghost;
+                }
+                if brain.is_some() && (is_button_clicked(&canvas, ai_button()) || is_key_pressed(KeyCode::A)) {
+                    ai_plays = !ai_plays;
                 }
 
-                // for pipe in &mut game_state.pipes {
-                //     pipe.position.x += PIPE_SPEED;
-                // }
-
-                update_pipes(&mut game_state.pipes);
-
-
-                for coin in &mut game_state.coins {
-                    coin.position.x += PIPE_SPEED;
+                if let Some(difficulty) = pick_difficulty(&canvas) {
+                    let ghost = if race_ghost { Ghost::load(&difficulty, obstacles) } else { None };
+                    game_state = GameState::new(difficulty, obstacles, ghost, bird_size);
+                    mode = GameMode::Resuming(get_time() + RESUME_COUNTDOWN);
                 }
+            }
 
-                game_state.pipes.retain(|pipe| pipe.position.x > -60.0);
+            GameMode::Playing if is_pause_pressed() => {
+                draw_game(&game_state, &textures, &bird_animation, show_hitboxes);
+                mode = GameMode::Paused;
+            }
 
-                // Collision with ground or ceiling
-                if game_state.bird.position.y <= 0.0 || game_state.bird.position.y + game_state.bird.radius >= SCREEN_HEIGHT {
+            GameMode::Playing => {
+                let flap = match (&brain, ai_plays) {
+                    (Some(brain), true) => brain.flaps(&game_state.observe()),
+                    _ => is_flap_pressed(&canvas),
+                };
+                let alive = game_state.update(flap);
+                bird_animation.play(if game_state.bird.velocity < 0.0 { FLAPPING } else { GLIDING });
+                bird_animation.update(get_frame_time());
+                let bird_y = game_state.bird.position.y;
+                game_state.recording.push(vec2(game_state.distance, bird_y));
+
+                if !alive {
+                    // Only your own runs become the ghost to beat
+                    new_best = !ai_plays && game_state.save_if_best();
                     mode = GameMode::GameOver;
                 }
 
-                // Collision with pipes
-                for pipe in &game_state.pipes {
-                    let bird_colliding = is_bird_colliding_with_pipe(&game_state.bird, &pipe);
-                    if bird_colliding {
-                            mode = GameMode::GameOver;
-                            break; // no need to check further pipes if we're restarting
-                    }
-                    // if game_state.bird.position.y < pipe.gap_y - 50.0 || game_state.bird.position.y > pipe.gap_y + 50.0 {
-                    //     if game_state.bird.position.x > pipe.position.x && game_state.bird.position.x < pipe.position.x + 60.0 {
-                    //         mode = GameMode::GameOver;
-                    //         break; // no need to check further pipes if we're restarting
-                    //     }
-                    // }
-                }
+                draw_game(&game_state, &textures, &bird_animation, show_hitboxes);
+            }
 
-                // collision with coins
-                game_state.coins.retain(|coin| {
-                    let collided = is_circle_colliding_with_bird(&game_state.bird, coin.position, coin.radius);
-                    if collided {
-                        game_state.score += 1;  // Assuming you have a score field in GameState
-                        game_state.speed_multiplier = 1.0 + 0.1 * game_state.score as f32;
-                    }
-                    !collided && coin.position.x + coin.radius > 0.0
-                });
-                 // ... [drawing code] ...
-
-
-                draw_texture_ex(
-                    &background_texture,
-                    0.0,
-                    0.0,
-                    WHITE,
-                    DrawTextureParams {
-                        dest_size: Some(vec2(SCREEN_WIDTH, SCREEN_HEIGHT)),
-                        ..Default::default()
-                    },
-                );
-                draw_game_frame();
+            GameMode::Paused => {
+                draw_game(&game_state, &textures, &bird_animation, show_hitboxes);
+                draw_overlay("Paused", "[Esc], [P] or flap to resume");
 
-                // Draw bird
-                // draw_circle(game_state.bird.position.x, game_state.bird.position.y, 20.0, YELLOW);
-                draw_texture_ex(&player_texture, game_state.bird.position.x, game_state.bird.position.y, WHITE,
-                    DrawTextureParams {
-                        dest_size: Some(vec2(player_texture.width() * player_scale_factor, player_texture.height() * player_scale_factor)),
-                        ..Default::default()  // use default values for other parameters
-                    },);
-
-                
-                // Draw Coins
-                for coin in &game_state.coins {
-                    draw_circle(coin.position.x, coin.position.y, coin.radius, GOLD);
+                if is_pause_pressed() || is_flap_pressed(&canvas) {
+                    mode = GameMode::Resuming(get_time() + RESUME_COUNTDOWN);
                 }
-                // Draw pipes
-                for pipe in &game_state.pipes {
-                    let color = Color::from_rgba(42, 37, 56, 255);
+            }
 
-                    draw_rectangle(pipe.position.x, pipe.position.y, pipe.size.x, pipe.size.y, color);
+            GameMode::Resuming(until) => {
+                draw_game(&game_state, &textures, &bird_animation, show_hitboxes);
+                let left = (until - get_time()).ceil().max(1.0);
+                draw_overlay(&format!("{}", left), "Get ready!");
 
-                    // draw_rectangle(pipe.position.x, 0.0, 60.0, pipe.gap_y - 90.0, DARKGREEN);
-                    // draw_rectangle(pipe.position.x, pipe.gap_y + 90.0, 60.0, SCREEN_HEIGHT - pipe.gap_y - 90.0, DARKGREEN);
+                if is_pause_pressed() {
+                    mode = GameMode::Paused;
+                } else if get_time() >= until {
+                    mode = GameMode::Playing;
                 }
-
-                // draw score
-                let score_text = format!("Score: {}", game_state.score);
-                draw_text(&score_text, 10.0, 30.0, 40.0, WHITE);
-
             }
 
             GameMode::GameOver => {
                 clear_background(Color::new(63.0 / 255.0, 145.0 / 255.0, 195.0 / 255.0, 1.0));
                 draw_game_frame();
 
-                
-                draw_text("Game Over", SCREEN_WIDTH / 2.0 - 100.0, SCREEN_HEIGHT / 2.0 - 20.0, 40.0, Color::new(42.0 / 255.0, 37.0 / 255.0, 56.0 / 255.0, 1.0));
-                draw_text("Click to restart!", SCREEN_WIDTH / 2.0 - 150.0, SCREEN_HEIGHT / 2.0 + 30.0, 30.0, Color::new(42.0 / 255.0, 37.0 / 255.0, 56.0 / 255.0, 1.0));
-            
+                draw_centered_text("Game Over", SCREEN_HEIGHT / 2.0 - 20.0, 60.0);
+                draw_centered_text(&format!("Score: {}", game_state.score), SCREEN_HEIGHT / 2.0 + 30.0, 40.0);
+                draw_centered_text("Flap to restart!", SCREEN_HEIGHT / 2.0 + 80.0, 30.0);
+                draw_centered_text("[Esc] to go back to the title screen", SCREEN_HEIGHT / 2.0 + 120.0, 30.0);
+                if new_best {
+                    draw_centered_text("New best run, its ghost flies with you next time", SCREEN_HEIGHT / 2.0 + 170.0, 30.0);
+                }
 
-                if is_mouse_button_pressed(MouseButton::Left) {
-                    game_state = GameState::new();
-                    mode = GameMode::Playing;
+                if is_flap_pressed(&canvas) {
+                    let (difficulty, obstacles) = (game_state.difficulty, game_state.obstacles);
+                    let ghost = if race_ghost { Ghost::load(&difficulty, obstacles) } else { None };
+                    game_state = GameState::new(difficulty, obstacles, ghost, bird_size);
+                    mode = GameMode::Resuming(get_time() + RESUME_COUNTDOWN);
+                } else if is_key_pressed(KeyCode::Escape) {
+                    mode = GameMode::Title;
                 }
             }
         }
-       
+
+        canvas.end();
         next_frame().await;
     }
 }
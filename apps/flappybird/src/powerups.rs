@@ -0,0 +1,171 @@
+use macroquad::prelude::*;
+
+pub const PICKUP_RADIUS: f32 = 22.0;
+const MAGNET_RADIUS: f32 = 250.0;  // coins closer than this fly to the bird
+const SLOW_TIME_SCALE: f32 = 0.6;  // scale on the scroll speed
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum Effect {
+    Shield,  // takes one pipe hit
+    Magnet,
+    SlowTime,
+}
+
+pub struct PowerUp {
+    pub effect: Effect,
+    pub name: &'static str,
+    // Shown on the pickup
+    pub letter: &'static str,
+    pub color: Color,
+    // In frames, the game advances a fixed step per frame
+    pub duration: u32,
+}
+
+// Pickups pick from this list at random, one entry per effect
+pub static POWER_UPS: &[PowerUp] = &[
+    PowerUp {
+        effect: Effect::Shield,
+        name: "Shield",
+        letter: "S",
+        color: SKYBLUE,
+        duration: 60 * 15,
+    },
+    PowerUp {
+        effect: Effect::Magnet,
+        name: "Magnet",
+        letter: "M",
+        color: RED,
+        duration: 60 * 10,
+    },
+    PowerUp {
+        effect: Effect::SlowTime,
+        name: "Slow time",
+        letter: "T",
+        color: PURPLE,
+        duration: 60 * 8,
+    },
+];
+
+/// A power-up scrolling along with the coins, waiting to be flown into.
+pub struct Pickup {
+    pub position: Vec2,
+    pub power_up: &'static PowerUp,
+}
+
+/// Frames left on each effect, 0 while it is off. Different effects run side by
+/// side, picking up one that is already running restarts its timer.
+#[derive(Default)]
+pub struct PowerUps {
+    frames_left: [u32; 3],
+}
+
+impl PowerUps {
+    pub fn collect(&mut self, power_up: &PowerUp) {
+        self.frames_left[power_up.effect as usize] = power_up.duration;
+    }
+
+    /// Counts down one frame.
+    pub fn update(&mut self) {
+        for frames in self.frames_left.iter_mut() {
+            *frames = frames.saturating_sub(1);
+        }
+    }
+
+    fn is_active(&self, effect: Effect) -> bool {
+        self.frames_left[effect as usize] > 0
+    }
+
+    pub fn shield(&self) -> bool {
+        self.is_active(Effect::Shield)
+    }
+
+    // 0 without a magnet
+    pub fn magnet_radius(&self) -> f32 {
+        if self.is_active(Effect::Magnet) { MAGNET_RADIUS } else { 0.0 }
+    }
+
+    pub fn time_scale(&self) -> f32 {
+        if self.is_active(Effect::SlowTime) { SLOW_TIME_SCALE } else { 1.0 }
+    }
+
+    /// Uses up the shield, returns false if there was none to absorb the hit.
+    pub fn absorb_hit(&mut self) -> bool {
+        let shielded = self.shield();
+        self.frames_left[Effect::Shield as usize] = 0;
+        shielded
+    }
+
+    /// Each active power-up with the share of its time left, for the HUD.
+    pub fn timers(&self) -> impl Iterator<Item = (&'static PowerUp, f32)> + '_ {
+        POWER_UPS.iter().filter(|power_up| self.is_active(power_up.effect)).map(|power_up| {
+            (power_up, self.frames_left[power_up.effect as usize] as f32 / power_up.duration as f32)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn power_up(effect: Effect) -> &'static PowerUp {
+        POWER_UPS.iter().find(|power_up| power_up.effect == effect).unwrap()
+    }
+
+    #[test]
+    fn every_effect_has_one_power_up() {
+        for effect in [Effect::Shield, Effect::Magnet, Effect::SlowTime] {
+            assert_eq!(POWER_UPS.iter().filter(|power_up| power_up.effect == effect).count(), 1);
+        }
+    }
+
+    #[test]
+    fn different_power_ups_stack() {
+        let mut power_ups = PowerUps::default();
+        power_ups.collect(power_up(Effect::Magnet));
+        power_ups.collect(power_up(Effect::SlowTime));
+        assert_eq!(power_ups.magnet_radius(), MAGNET_RADIUS);
+        assert_eq!(power_ups.time_scale(), SLOW_TIME_SCALE);
+        assert!(!power_ups.shield());
+        assert_eq!(power_ups.timers().count(), 2);
+    }
+
+    #[test]
+    fn collecting_a_running_power_up_restarts_it() {
+        let mut power_ups = PowerUps::default();
+        let magnet = power_up(Effect::Magnet);
+        power_ups.collect(magnet);
+        for _ in 0..100 {
+            power_ups.update();
+        }
+        power_ups.collect(magnet);
+        assert_eq!(power_ups.timers().collect::<Vec<_>>().len(), 1);
+        assert_eq!(power_ups.timers().next().unwrap().1, 1.0);
+    }
+
+    #[test]
+    fn power_ups_expire_after_their_duration() {
+        let mut power_ups = PowerUps::default();
+        let slow_time = power_up(Effect::SlowTime);
+        power_ups.collect(slow_time);
+        for _ in 1..slow_time.duration {
+            power_ups.update();
+        }
+        assert_eq!(power_ups.time_scale(), SLOW_TIME_SCALE);
+        power_ups.update();
+        assert_eq!(power_ups.time_scale(), 1.0);
+        assert_eq!(power_ups.timers().count(), 0);
+    }
+
+    #[test]
+    fn shield_absorbs_one_hit() {
+        let mut power_ups = PowerUps::default();
+        assert!(!power_ups.absorb_hit());
+        power_ups.collect(power_up(Effect::Shield));
+        power_ups.collect(power_up(Effect::Magnet));
+        assert!(power_ups.absorb_hit());
+        assert!(!power_ups.shield());
+        assert!(!power_ups.absorb_hit());
+        // Other power-ups keep running
+        assert_eq!(power_ups.magnet_radius(), MAGNET_RADIUS);
+    }
+}
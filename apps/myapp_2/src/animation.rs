@@ -1,5 +1,5 @@
-use bevy::prelude::*;
-pub struct AnimationPlugin;
+use crate::timer;
+use timer::*;
 
 pub struct AnimationFrame {
     pub index: i32,
@@ -16,42 +16,30 @@ pub struct Animations {
     pub current_animation: i32,
 }
 
-impl Plugin for AnimationPlugin {
-    fn build(&self, app: &mut AppBuilder) {
-        app.add_system(animate_system.system());
-    }
-}
-
-fn animate_system(
-    mut query: Query<(
-        &mut Timer,
-        &mut TextureAtlasSprite,
-        &mut Animations,
-    )>,
+// Moves to the next frame whenever the timer runs out, `sprite_index` is the sheet cell to show
+pub fn animate_system(
+    dt: f32,
+    timer: &mut Timer,
+    sprite_index: &mut u32,
+    animations: &mut Animations,
 ) {
-    for (mut timer, mut sprite, mut animations) in &mut query.iter() {
-        if timer.finished {
-            let current_animation_index = animations.current_animation;
-            match animations
-                .animations
-                .get_mut(current_animation_index as usize)
-            {
-                Some(animation) => {
-                    animation.current_frame += 1;
-                    if animation.current_frame as usize >= animation.frames.len() {
-                        animation.current_frame = 0;
-                    }
-                    let frame_data = animation
-                        .frames
-                        .get(animation.current_frame as usize)
-                        .unwrap();
-                    timer.duration = frame_data.time;
-                    if let Some(frame) = animation.frames.get(animation.current_frame as usize) {
-                        sprite.index = frame.index as u32;
-                    }
-                }
-                None => {}
+    timer.tick(dt);
+    if timer.finished {
+        let current_animation_index = animations.current_animation;
+        if let Some(animation) = animations
+            .animations
+            .get_mut(current_animation_index as usize)
+        {
+            animation.current_frame += 1;
+            if animation.current_frame as usize >= animation.frames.len() {
+                animation.current_frame = 0;
             }
+            let frame_data = animation
+                .frames
+                .get(animation.current_frame as usize)
+                .unwrap();
+            timer.duration = frame_data.time;
+            *sprite_index = frame_data.index as u32;
         }
     }
 }
@@ -1,26 +1,29 @@
-use bevy::{
-    input::{keyboard::KeyCode, Input},
-    prelude::*,
-    sprite::collide_aabb::{collide},
-};
-
 use crate::animation;
 use crate::gamedata;
 use crate::gamestate;
 use crate::physics;
 use crate::pipes;
 use crate::screens;
+use crate::sprite;
+use crate::timer;
+use crate::worldconfig;
 
 use animation::*;
 use gamedata::*;
 use gamestate::*;
+use macroquad::prelude::*;
 use physics::*;
 use pipes::*;
 use screens::*;
+use sprite::*;
+use timer::*;
+use worldconfig::*;
 
-pub struct Player;
 pub struct JumpHeight(pub f32);
 
+// Columns and rows of cells in bird.png
+pub const BIRD_SHEET: (u32, u32) = (2, 2);
+
 // data for animating rotation
 pub struct VelocityRotator {
     pub angle_up: f32,
@@ -29,184 +32,134 @@ pub struct VelocityRotator {
     pub velocity_max: f32,
 }
 
-pub struct BirdPlugin;
+pub struct Player {
+    pub sprite: Sprite,
+    pub sprite_index: u32,
+    pub velocity: Velocity,
+    pub velocity_rotator: VelocityRotator,
+    pub animations: Animations,
+    pub timer: Timer,
+}
+
+impl Player {
+    pub fn update(&mut self, dt: f32) {
+        velocity_rotator_system(self);
+        velocity_animator_system(self);
+        animate_system(
+            dt,
+            &mut self.timer,
+            &mut self.sprite_index,
+            &mut self.animations,
+        );
+        self.sprite
+            .set_atlas_index(BIRD_SHEET.0, BIRD_SHEET.1, self.sprite_index);
+    }
 
-impl Plugin for BirdPlugin {
-    fn build(&self, app: &mut AppBuilder) {
-        app.add_system(player_input.system())
-            .add_system(player_bounds_system.system())
-            .add_system(player_collision_system.system())
-            .add_system(velocity_rotator_system.system())
-            .add_system(velocity_animator_system.system());
+    pub fn draw(&self) {
+        self.sprite.draw();
     }
 }
 
-fn player_input(
-    game_data: Res<GameData>,
-    jump_height: Res<JumpHeight>,
-    keyboard_input: Res<Input<KeyCode>>,
-    _player: Mut<Player>,
-    translation: Mut<Translation>,
-    velocity: Mut<Velocity>,
-) {
+pub fn player_input(game_data: &GameData, jump_height: &JumpHeight, player: &mut Player) {
     match game_data.game_state {
         GameState::Menu => {
-            handle_stay_in_screen(jump_height, velocity, translation);
+            handle_stay_in_screen(jump_height, player);
         }
         GameState::Playing => {
-            handle_jump(keyboard_input, jump_height, velocity);
+            handle_jump(jump_height, player);
         }
         GameState::Dead => {}
     }
 }
 
 // Auto jump until input is given
-fn handle_stay_in_screen(
-    jump_height: Res<JumpHeight>,
-    mut velocity: Mut<Velocity>,
-    translation: Mut<Translation>,
-) {
-    if translation.0.y() < 0.0 {
-        velocity.0.set_y(jump_height.0);
+fn handle_stay_in_screen(jump_height: &JumpHeight, player: &mut Player) {
+    if player.sprite.position.y < 0.0 {
+        player.velocity.0.y = jump_height.0;
     }
 }
 
-fn handle_jump(
-    keyboard_input: Res<Input<KeyCode>>,
-    jump_height: Res<JumpHeight>,
-    mut velocity: Mut<Velocity>,
-) {
-    if keyboard_input.just_pressed(KeyCode::Space) {
-        velocity.0.set_y(jump_height.0);
+fn handle_jump(jump_height: &JumpHeight, player: &mut Player) {
+    if is_key_pressed(KeyCode::Space) {
+        player.velocity.0.y = jump_height.0;
     }
 }
 
-fn player_bounds_system(
-    mut commands: Commands,
-    mut game_data: ResMut<GameData>,
-    mut player_query: Query<(&Player, &mut Translation, &mut Velocity)>,
-    mut pipe_query: Query<(&Pipe, &Translation, &Collider, &Sprite, Entity)>,
-    mut score_collider_query: Query<(&Translation, &Collider, Entity)>,
-    mut end_screen_query: Query<(&EndScreen, &mut Draw)>,
+pub fn player_bounds_system(
+    world_config: &WorldConfig,
+    game_data: &mut GameData,
+    player: &mut Player,
+    pipes: &mut Pipes,
+    screens: &mut Screens,
 ) {
-    let half_screen_size = 1280.0 * 0.5;
-    let player_size = 32.0 * 6.0;
-    for (_p, mut translation, mut velocity) in &mut player_query.iter() {
-        // bounce against ceiling
-        if translation.0.y() > half_screen_size - player_size {
-            velocity.0.set_y(-3.0);
-            translation.0.set_y(half_screen_size - player_size);
-        }
-        // death on bottom touch
-        if translation.0.y() < -half_screen_size {
-            trigger_death(
-                &mut commands,
-                &mut game_data,
-                &mut pipe_query,
-                &mut score_collider_query,
-                &mut end_screen_query,
-            );
-        }
+    let half_screen_size = world_config.half_play_area().y;
+    let player_size = world_config.bird_world_size().y;
+    // bounce against ceiling
+    if player.sprite.position.y > half_screen_size - player_size {
+        player.velocity.0.y = -3.0;
+        player.sprite.position.y = half_screen_size - player_size;
+    }
+    // death on bottom touch
+    if player.sprite.position.y < -half_screen_size {
+        trigger_death(game_data, pipes, screens);
     }
 }
 
-fn player_collision_system(
-    mut commands: Commands,
-    mut game_data: ResMut<GameData>,
-    mut worlds: Query<&mut World>,
-    mut player_query: Query<(&Player, &Translation)>,
-    mut pipe_query: Query<(&Pipe, &Translation, &Collider, &Sprite, Entity)>,
-    mut score_collider_query: Query<(&Translation, &Collider, Entity)>,
-    mut end_screen_query: Query<(&EndScreen, &mut Draw)>,
+pub fn player_collision_system(
+    world_config: &WorldConfig,
+    game_data: &mut GameData,
+    player: &Player,
+    pipes: &mut Pipes,
+    screens: &mut Screens,
 ) {
-    // Player size can't be fetched from AtlasTextureSprite, so I'm hard coding it here...
-    let mut player_size = 6.0 * 32.0;
+    let mut player_size = world_config.bird_world_size().x;
     // Make player hitbox half size, to feel more fair
     player_size *= 0.4;
-    let player_size_vec = (player_size, player_size);
-    for (_player, player_translation) in &mut player_query.iter() {
-        for (translation, collider, entity) in &mut score_collider_query.iter() {
-            if *collider != Collider::ScoreGiver {
-                continue;
-            }
-            let collision = collide(
-                player_translation.0,
-                player_size_vec.into(),
-                translation.0,
-                Vec2::new(10.0, 1280.0),
-            );
-            if collision.is_some() {
-                game_data.score += 1;
-                println!("got score!: {}", game_data.score);
-                // Remove coin collider, quick simple solution
-                for world in &mut worlds.iter() {
-                    if !world.contains(entity) {
-                        commands.despawn(entity);
-                    }
-                }
-            }
-        }
-        // Check for collision
-        let mut did_collide = false;
-        for (_pipe, pipe_translation, _collider, pipe_sprite, _pipe_entity) in
-            &mut pipe_query.iter()
-        {
-            let collision = collide(
-                player_translation.0,
-                player_size_vec.into(),
-                pipe_translation.0,
-                pipe_sprite.size * 6.0,
-            );
-            if collision.is_some() {
-                did_collide = true;
-                break;
-            }
-        }
-        if did_collide {
-            trigger_death(
-                &mut commands,
-                &mut game_data,
-                &mut pipe_query,
-                &mut score_collider_query,
-                &mut end_screen_query,
-            );
-        }
+    let player_size_vec = vec2(player_size, player_size);
+    let player_position = player.sprite.position;
+
+    let before = pipes.score_givers.len();
+    // Remove score givers once passed through, quick simple solution
+    pipes.score_givers.retain(|score_giver| {
+        !collide(
+            player_position,
+            player_size_vec,
+            score_giver.position,
+            vec2(10.0, world_config.play_area.y),
+        )
+    });
+    game_data.score += (before - pipes.score_givers.len()) as i32;
+
+    // Check for collision
+    let did_collide = pipes.pipes.iter().any(|pipe| {
+        collide(
+            player_position,
+            player_size_vec,
+            pipe.sprite.position,
+            world_config.pipe_world_size(),
+        )
+    });
+    if did_collide {
+        trigger_death(game_data, pipes, screens);
     }
 }
 
-fn trigger_death(
-    commands: &mut Commands,
-    game_data: &mut ResMut<GameData>,
-    pipe_query: &mut Query<(&Pipe, &Translation, &Collider, &Sprite, Entity)>,
-    score_query: &mut Query<(&Translation, &Collider, Entity)>,
-    end_screen_query: &mut Query<(&EndScreen, &mut Draw)>,
-) {
+fn trigger_death(game_data: &mut GameData, pipes: &mut Pipes, screens: &mut Screens) {
     game_data.game_state = GameState::Dead;
-    game_data.score = 0;
-    // Despawn all pipes
-    for (_p, _pt, _c, _ps, pipe_entity) in &mut pipe_query.iter() {
-        commands.despawn(pipe_entity);
-    }
-    // Despawn score colliders
-    for (_t, collider, score_entity) in &mut score_query.iter() {
-        if *collider == Collider::ScoreGiver {
-            commands.despawn(score_entity);
-        }
-    }
-    for (_es, mut draw) in &mut end_screen_query.iter() {
-        draw.is_visible = true;
+    // The score stays up on the end screen until the next run starts
+    if game_data.score > game_data.best_score {
+        game_data.best_score = game_data.score;
+        save_best_score(game_data.best_score);
     }
+    // Despawn all pipes and score givers
+    pipes.clear();
+    screens.end_screen.is_visible = true;
 }
 
-fn velocity_rotator_system(
-    velocity: Mut<Velocity>,
-    mut rotation: Mut<Rotation>,
-    velocity_rotator: Mut<VelocityRotator>,
-) {
-    //let quat = Quat::from_rotation_z(velocity_rotator.).lerp();
-    let mut procentage = velocity.0.y() / velocity_rotator.velocity_max;
-    procentage = procentage.max(-1.0);
-    procentage = procentage.min(1.0);
+fn velocity_rotator_system(player: &mut Player) {
+    let velocity_rotator = &player.velocity_rotator;
+    let mut procentage = player.velocity.0.y / velocity_rotator.velocity_max;
+    procentage = procentage.clamp(-1.0, 1.0);
     // convert from -1 -> 1 to: 0 -> 1
     procentage = (procentage + 1.0) * 0.5;
 
@@ -214,55 +167,32 @@ fn velocity_rotator_system(
     let rad_angle =
         (1.0 - procentage) * velocity_rotator.angle_down + procentage * velocity_rotator.angle_up;
 
-    rotation.0 = Quat::from_rotation_z(rad_angle);
+    player.sprite.rotation = rad_angle;
 }
 
-fn velocity_animator_system(mut query: Query<(&mut Animations, &Velocity)>) {
-    for (mut animations, velocity) in &mut query.iter() {
-        if velocity.0.y() > 0.0 {
-            animations.current_animation = 0;
-        } else {
-            animations.current_animation = 1;
-        }
+fn velocity_animator_system(player: &mut Player) {
+    if player.velocity.0.y > 0.0 {
+        player.animations.current_animation = 0;
+    } else {
+        player.animations.current_animation = 1;
     }
 }
 
-pub fn spawn_bird(
-    commands: &mut Commands,
-    asset_server: &mut Res<AssetServer>,
-    mut textures: &mut ResMut<Assets<Texture>>,
-    texture_atlases: &mut ResMut<Assets<TextureAtlas>>,
-) {
-    let texture_handle = asset_server
-        .load_sync(&mut textures, "assets/bird.png")
-        .unwrap();
+pub fn spawn_bird(world_config: &WorldConfig, texture: &Texture2D) -> Player {
+    let mut sprite = Sprite::new(texture, Vec2::ZERO, world_config.sprite_scale);
+    sprite.set_atlas_index(BIRD_SHEET.0, BIRD_SHEET.1, 0);
 
-    let texture = textures.get(&texture_handle).unwrap();
-    let texture_atlas = TextureAtlas::from_grid(texture_handle, texture.size, 2, 2);
-    let texture_atlas_handle = texture_atlases.add(texture_atlas);
-
-    commands
-        .spawn(SpriteSheetComponents {
-            texture_atlas: texture_atlas_handle,
-            scale: Scale(6.0),
-            translation: Translation::new(0.0, 0.0, 100.0),
-            draw: Draw {
-                is_transparent: true,
-                is_visible: true,
-                render_commands: Vec::new(),
-            },
-            ..Default::default()
-        })
-        .with(Timer::from_seconds(0.1, true))
-        .with(Player)
-        .with(AffectedByGravity)
-        .with(VelocityRotator {
+    Player {
+        sprite,
+        sprite_index: 0,
+        timer: Timer::from_seconds(0.1),
+        velocity_rotator: VelocityRotator {
             angle_up: std::f32::consts::PI * 0.5 * 0.7,
             angle_down: -std::f32::consts::PI * 0.5 * 0.5,
             velocity_max: 400.0,
-        })
-        .with(Velocity(Vec2::zero()))
-        .with(Animations {
+        },
+        velocity: Velocity(Vec2::ZERO),
+        animations: Animations {
             animations: vec![
                 Animation {
                     current_frame: 0,
@@ -294,5 +224,6 @@ pub fn spawn_bird(
                 },
             ],
             current_animation: 0,
-        });
+        },
+    }
 }
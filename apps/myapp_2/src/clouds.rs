@@ -1,48 +1,71 @@
+use crate::bounds_deletion;
 use crate::physics;
-use bevy::prelude::*;
+use crate::sprite;
+use crate::timer;
+use crate::worldconfig;
+use bounds_deletion::*;
+use macroquad::prelude::*;
+use macroquad::rand::gen_range;
 use physics::*;
-use rand::{thread_rng, Rng};
+use sprite::*;
+use timer::*;
+use worldconfig::*;
 
-pub struct CloudTimer(Timer);
+pub struct Cloud {
+    pub sprite: Sprite,
+    pub velocity: Velocity,
+}
 
-pub struct CloudPlugin;
+pub struct Clouds {
+    pub clouds: Vec<Cloud>,
+    cloud_timer: Timer,
+    cloud_textures: [Texture2D; 2],
+}
 
-impl Plugin for CloudPlugin {
-    fn build(&self, app: &mut AppBuilder) {
-        app.add_system(cloud_spawn_system.system())
-            .add_resource(CloudTimer(Timer::from_seconds(1.0, true)));
+impl Clouds {
+    pub async fn load() -> Self {
+        Clouds {
+            clouds: Vec::new(),
+            cloud_timer: Timer::from_seconds(1.0),
+            cloud_textures: [
+                load_sprite_texture("assets/cloud_1.png").await,
+                load_sprite_texture("assets/cloud_2.png").await,
+            ],
+        }
     }
-}
 
-fn cloud_spawn_system(
-    mut commands: Commands,
-    time: Res<Time>,
-    mut cloud_timer: ResMut<CloudTimer>,
-    asset_server: Res<AssetServer>,
-    mut materials: ResMut<Assets<ColorMaterial>>,
-) {
-    let mut rng = thread_rng();
-    let cloud_texture = match rng.gen_bool(0.5) {
-        true => asset_server.load("assets/cloud_1.png").unwrap(),
-        false => asset_server.load("assets/cloud_2.png").unwrap(),
-    };
+    pub fn update(&mut self, world_config: &WorldConfig, dt: f32) {
+        self.cloud_spawn_system(world_config, dt);
+        for cloud in self.clouds.iter_mut() {
+            velocity_system(dt, &mut cloud.sprite.position, &cloud.velocity);
+        }
+        self.clouds.retain(|cloud| {
+            !is_offscreen(world_config, cloud.sprite.position, cloud.sprite.size())
+        });
+    }
+
+    pub fn draw(&self) {
+        for cloud in self.clouds.iter() {
+            cloud.sprite.draw();
+        }
+    }
 
-    cloud_timer.0.tick(time.delta_seconds);
-    if cloud_timer.0.finished {
-        commands
-            .spawn(SpriteComponents {
-                material: materials.add(cloud_texture.into()),
-                scale: Scale(rng.gen_range(6.0, 30.0)),
-                translation: Translation(Vec3::new(
-                    1920.0 * 0.5 + 30.0 * 43.0,
-                    rng.gen_range(-1280.0 * 0.5, 1280.0 * 0.5),
-                    2.0,
-                )),
-                ..Default::default()
-            })
-            .with(Velocity(Vec2::new(
-                rng.gen_range(-700.0, -400.0),
-                rng.gen_range(-10.0, 10.0),
-            )));
+    fn cloud_spawn_system(&mut self, world_config: &WorldConfig, dt: f32) {
+        self.cloud_timer.tick(dt);
+        if self.cloud_timer.finished {
+            let half_play_area = world_config.half_play_area();
+            let cloud_texture = &self.cloud_textures[gen_range(0, 2)];
+            let scale = world_config.sprite_scale * gen_range(1.0, 5.0);
+            let mut sprite = Sprite::new(cloud_texture, Vec2::ZERO, scale);
+            // Just out of sight on the right
+            sprite.position = vec2(
+                half_play_area.x + sprite.size().x * 0.5,
+                gen_range(-half_play_area.y, half_play_area.y),
+            );
+            self.clouds.push(Cloud {
+                sprite,
+                velocity: Velocity(vec2(gen_range(-700.0, -400.0), gen_range(-10.0, 10.0))),
+            });
+        }
     }
 }
@@ -1,7 +1,22 @@
 use crate::gamestate;
 use gamestate::GameState;
 
+// Saved by the storage library, survives closing the game
+const BEST_SCORE_KEY: &str = "myapp_2_best_score";
+
 pub struct GameData {
     pub game_state: GameState,
+    // Score of the current run, kept after death for the end screen
     pub score: i32,
+    pub best_score: i32,
+}
+
+pub fn load_best_score() -> i32 {
+    storage::load(BEST_SCORE_KEY)
+        .and_then(|value| value.trim().parse().ok())
+        .unwrap_or(0)
+}
+
+pub fn save_best_score(score: i32) {
+    storage::save(BEST_SCORE_KEY, &score.to_string());
 }
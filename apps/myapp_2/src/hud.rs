@@ -0,0 +1,66 @@
+use crate::gamedata;
+use crate::gamestate;
+use crate::worldconfig;
+use gamedata::*;
+use gamestate::*;
+use macroquad::prelude::*;
+use worldconfig::*;
+
+// Drawn in screen pixels, after the world camera is switched off
+pub struct Hud {
+    font: Font,
+}
+
+impl Hud {
+    pub async fn load() -> Self {
+        Hud {
+            font: load_ttf_font("assets/fonts/Kenney Future Narrow.ttf")
+                .await
+                .unwrap(),
+        }
+    }
+
+    pub fn draw(&self, world_config: &WorldConfig, game_data: &GameData) {
+        // Sized like the world, which always fills the window's height
+        let scale = screen_height() / world_config.play_area.y;
+        match game_data.game_state {
+            // Current score, top left while playing
+            GameState::Playing => {
+                let font_size = 120.0 * scale;
+                self.draw_text(
+                    &game_data.score.to_string(),
+                    40.0 * scale,
+                    20.0 * scale + font_size,
+                    font_size,
+                );
+            }
+            // Final and best score, under the game over text
+            GameState::Dead => {
+                let text = format!("Score {}   Best {}", game_data.score, game_data.best_score);
+                let font_size = 80.0 * scale;
+                let width = measure_text(&text, Some(&self.font), font_size as u16, 1.0).width;
+                self.draw_text(
+                    &text,
+                    (screen_width() - width) * 0.5,
+                    screen_height() * 0.7,
+                    font_size,
+                );
+            }
+            GameState::Menu => {}
+        }
+    }
+
+    fn draw_text(&self, text: &str, x: f32, y: f32, font_size: f32) {
+        draw_text_ex(
+            text,
+            x,
+            y,
+            TextParams {
+                font: Some(&self.font),
+                font_size: font_size as u16,
+                color: WHITE,
+                ..Default::default()
+            },
+        );
+    }
+}
@@ -1,58 +1,134 @@
-use bevy::prelude::*;
+use macroquad::prelude::*;
 mod animation;
 mod bird;
 mod bounds_deletion;
 mod clouds;
 mod gamedata;
 mod gamestate;
+mod hud;
 mod mountains;
 mod physics;
 mod pipes;
 mod screens;
+mod sprite;
+mod timer;
+mod worldconfig;
 
-use animation::*;
 use bird::*;
 use clouds::*;
 use gamedata::*;
 use gamestate::*;
+use hud::*;
 use mountains::*;
 use physics::*;
 use pipes::*;
 use screens::*;
+use sprite::*;
+use worldconfig::*;
 
-fn main() {
-    App::build()
-        .add_default_plugins()
-        .add_plugin(PipePlugin)
-        .add_plugin(BirdPlugin)
-        .add_plugin(CloudPlugin)
-        .add_plugin(MountainPlugin)
-        .add_plugin(AnimationPlugin)
-        .add_plugin(PhysicsPlugin)
-        .add_plugin(ScreensPlugin)
-        .add_plugin(GameStatePlugin)
-        .add_startup_system(setup.system())
-        .add_resource(ClearColor(Color::rgb(0.34, 0.75, 0.79)))
-        .add_resource(JumpHeight(23.0 * 40.0))
-        .add_resource(Gravity(45.0 * 40.0))
-        .add_resource(GameData {
-            game_state: GameState::Menu,
-            score: 0,
-        })
-        .run();
+fn window_conf() -> Conf {
+    Conf {
+        window_title: "Flappy Bevy".to_owned(),
+        window_width: 1280,
+        window_height: 853,
+        ..Default::default()
+    }
 }
 
-fn setup(
-    mut commands: Commands,
-    mut asset_server: Res<AssetServer>,
-    mut textures: ResMut<Assets<Texture>>,
-    mut texture_atlases: ResMut<Assets<TextureAtlas>>,
-) {
-    commands.spawn(Camera2dComponents::default());
-    bird::spawn_bird(
-        &mut commands,
-        &mut asset_server,
-        &mut textures,
-        &mut texture_atlases,
-    );
+// Shows the play area with its origin in the middle of the screen and y
+// pointing up, like the bevy camera. Macroquad flips y when drawing to the
+// screen, so a negative zoom.y is what keeps the ceiling at the top
+fn world_camera(world_config: &WorldConfig) -> Camera2D {
+    Camera2D {
+        zoom: vec2(
+            2.0 / world_config.play_area.x,
+            -2.0 / world_config.play_area.y,
+        ),
+        ..Default::default()
+    }
+}
+
+#[macroquad::main(window_conf)]
+async fn main() {
+    // The web build serves public/, look for the assets in the same place natively
+    set_pc_assets_folder("public");
+    macroquad::rand::srand(miniquad::date::now() as u64);
+
+    let jump_height = JumpHeight(23.0 * 40.0);
+    let gravity = Gravity(45.0 * 40.0);
+    let mut game_data = GameData {
+        game_state: GameState::Menu,
+        score: 0,
+        best_score: load_best_score(),
+    };
+    let bird_texture = load_sprite_texture("assets/bird.png").await;
+    let pipe_texture = load_sprite_texture("assets/pipe.png").await;
+    let bird_cells = vec2(BIRD_SHEET.0 as f32, BIRD_SHEET.1 as f32);
+    let mut world_config = WorldConfig::new(bird_texture.size() / bird_cells, pipe_texture.size());
+    let mut player = spawn_bird(&world_config, &bird_texture);
+    let mut pipes = Pipes::new(pipe_texture);
+    let mut clouds = Clouds::load().await;
+    let mut mountains = Mountains::load().await;
+    let mut screens = Screens::load().await;
+    let hud = Hud::load().await;
+
+    loop {
+        let dt = get_frame_time();
+        world_config.fit_window();
+
+        handle_gamestate_system(&mut game_data, &mut player, &mut screens);
+        player_input(&game_data, &jump_height, &mut player);
+        gravity_system(&gravity, dt, &mut player.velocity);
+        velocity_system(dt, &mut player.sprite.position, &player.velocity);
+        player_bounds_system(
+            &world_config,
+            &mut game_data,
+            &mut player,
+            &mut pipes,
+            &mut screens,
+        );
+        player_collision_system(
+            &world_config,
+            &mut game_data,
+            &player,
+            &mut pipes,
+            &mut screens,
+        );
+        player.update(dt);
+        pipes.update(&world_config, &game_data, dt);
+        clouds.update(&world_config, dt);
+        mountains.update(&world_config, dt);
+
+        clear_background(Color::new(0.34, 0.75, 0.79, 1.0));
+        set_camera(&world_camera(&world_config));
+        mountains.draw();
+        clouds.draw();
+        pipes.draw();
+        player.draw();
+        screens.draw();
+        set_default_camera();
+        hud.draw(&world_config, &game_data);
+
+        next_frame().await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn world_camera_puts_the_ceiling_at_the_top() {
+        let world_config = WorldConfig::new(vec2(32.0, 32.0), vec2(32.0, 128.0));
+        let matrix = world_camera(&world_config).matrix();
+        let half = world_config.half_play_area();
+
+        let ceiling = matrix.project_point3(vec3(0.0, half.y, 0.0));
+        let floor = matrix.project_point3(vec3(0.0, -half.y, 0.0));
+        let right = matrix.project_point3(vec3(half.x, 0.0, 0.0));
+
+        assert!((ceiling.y - 1.0).abs() < 1e-5);
+        assert!((floor.y + 1.0).abs() < 1e-5);
+        assert!((right.x - 1.0).abs() < 1e-5);
+    }
 }
@@ -1,64 +1,88 @@
 use crate::bounds_deletion;
 use crate::physics;
-use bevy::prelude::*;
+use crate::sprite;
+use crate::timer;
+use crate::worldconfig;
 use bounds_deletion::*;
+use macroquad::prelude::*;
 use physics::*;
-use rand::{thread_rng, Rng};
+use sprite::*;
+use timer::*;
+use worldconfig::*;
 
-// Spawn mountains with a delay
-pub struct MountainTimer(pub Timer);
+pub struct Mountain {
+    pub sprite: Sprite,
+    pub velocity: Velocity,
+}
 
-pub struct MountainPlugin;
+// Two rows of mountains, the far one slower and lighter
+pub struct Mountains {
+    pub far: Vec<Mountain>,
+    pub near: Vec<Mountain>,
+    // Spawn mountains with a delay
+    mountain_timer: Timer,
+    mountain_texture: Texture2D,
+}
 
-impl Plugin for MountainPlugin {
-    fn build(&self, app: &mut AppBuilder) {
-        app.add_system(mountain_spawn_system.system())
-            .add_resource(MountainTimer(Timer::from_seconds(3.0, true)));
+impl Mountains {
+    pub async fn load() -> Self {
+        Mountains {
+            far: Vec::new(),
+            near: Vec::new(),
+            mountain_timer: Timer::from_seconds(3.0),
+            mountain_texture: load_sprite_texture("assets/mountain.png").await,
+        }
     }
-}
 
-fn mountain_spawn_system(
-    mut commands: Commands,
-    time: Res<Time>,
-    mut mountain_timer: ResMut<MountainTimer>,
-    asset_server: Res<AssetServer>,
-    mut materials: ResMut<Assets<ColorMaterial>>,
-) {
-    let mut rng = thread_rng();
-    let mountain_texture = match rng.gen_bool(0.5) {
-        true => asset_server.load("assets/mountain.png").unwrap(),
-        false => asset_server.load("assets/mountain.png").unwrap(),
-    };
+    pub fn update(&mut self, world_config: &WorldConfig, dt: f32) {
+        self.mountain_spawn_system(world_config, dt);
+        for mountains in [&mut self.far, &mut self.near] {
+            for mountain in mountains.iter_mut() {
+                velocity_system(dt, &mut mountain.sprite.position, &mountain.velocity);
+            }
+            mountains.retain(|mountain| {
+                !is_offscreen(
+                    world_config,
+                    mountain.sprite.position,
+                    mountain.sprite.size(),
+                )
+            });
+        }
+    }
+
+    pub fn draw(&self) {
+        for mountain in self.far.iter().chain(self.near.iter()) {
+            mountain.sprite.draw();
+        }
+    }
 
-    mountain_timer.0.tick(time.delta_seconds);
-    if mountain_timer.0.finished {
-        commands
-            .spawn(SpriteComponents {
-                scale: Scale(3.0),
-                material: materials.add(ColorMaterial::modulated_texture(
-                    mountain_texture,
-                    Color::rgb(0.36, 0.36, 0.36),
-                )),
-                translation: Translation(Vec3::new(1920.0 * 0.5 + 30.0 * 43.0, -1280.0 * 0.5, 0.2)),
-                ..Default::default()
-            })
-            .with(OffsceenDeletion)
-            .with(Velocity(Vec2::new(-200.0, 0.0)));
-        commands
-            .spawn(SpriteComponents {
-                scale: Scale(3.0),
-                translation: Translation(Vec3::new(
-                    1920.0 * 0.5 + 30.0 * 43.0,
-                    -1280.0 * 0.5 - 100.0,
-                    0.3,
-                )),
-                material: materials.add(ColorMaterial::modulated_texture(
-                    mountain_texture,
-                    Color::rgb(0.26, 0.26, 0.26),
-                )),
-                ..Default::default()
-            })
-            .with(OffsceenDeletion)
-            .with(Velocity(Vec2::new(-400.0, 0.0)));
+    fn mountain_spawn_system(&mut self, world_config: &WorldConfig, dt: f32) {
+        self.mountain_timer.tick(dt);
+        if self.mountain_timer.finished {
+            let half_play_area = world_config.half_play_area();
+            let scale = world_config.sprite_scale * 0.5;
+            // Just out of sight on the right
+            let x_pos = half_play_area.x + self.mountain_texture.width() * scale * 0.5;
+            let mut far = Sprite::new(
+                &self.mountain_texture,
+                vec2(x_pos, -half_play_area.y),
+                scale,
+            );
+            far.color = Color::new(0.36, 0.36, 0.36, 1.0);
+            self.far.push(Mountain {
+                sprite: far,
+                velocity: Velocity(vec2(-200.0, 0.0)),
+            });
+            let mut near = Sprite::new(
+                &self.mountain_texture,
+                vec2(x_pos, -half_play_area.y - 100.0),
+                scale,
+            );
+            near.color = Color::new(0.26, 0.26, 0.26, 1.0);
+            self.near.push(Mountain {
+                sprite: near,
+                velocity: Velocity(vec2(-400.0, 0.0)),
+            });
+        }
     }
 }
@@ -1,32 +1,20 @@
-use bevy::prelude::*;
+use macroquad::prelude::*;
 
 pub struct Velocity(pub Vec2);
 
 pub struct Gravity(pub f32);
-pub struct AffectedByGravity;
 
-pub struct PhysicsPlugin;
-
-impl Plugin for PhysicsPlugin {
-    fn build(&self, app: &mut AppBuilder) {
-        app.add_system(velocity_system.system())
-            .add_system(gravity_system.system());
-    }
+pub fn gravity_system(gravity: &Gravity, dt: f32, velocity: &mut Velocity) {
+    velocity.0.y -= gravity.0 * dt;
 }
 
-fn gravity_system(
-    gravity: Res<Gravity>,
-    time: Res<Time>,
-    _affected_by_gravity: &AffectedByGravity,
-    mut velocity: Mut<Velocity>,
-) {
-    *velocity.0.y_mut() -= gravity.0 * time.delta_seconds;
+pub fn velocity_system(dt: f32, position: &mut Vec2, velocity: &Velocity) {
+    *position += velocity.0 * dt;
 }
 
-fn velocity_system(time: Res<Time>, mut position: Mut<Translation>, velocity: Mut<Velocity>) {
-    let y = position.0.y();
-    let x = position.0.x();
-    let delta = time.delta_seconds;
-    position.0.set_y(y + velocity.0.y() * delta);
-    position.0.set_x(x + velocity.0.x() * delta);
+// Same as bevy's collide_aabb, both boxes are centered on their position
+pub fn collide(a_pos: Vec2, a_size: Vec2, b_pos: Vec2, b_size: Vec2) -> bool {
+    let distance = (a_pos - b_pos).abs();
+    let reach = (a_size + b_size) * 0.5;
+    distance.x < reach.x && distance.y < reach.y
 }
@@ -2,14 +2,29 @@ use crate::bounds_deletion;
 use crate::gamedata;
 use crate::gamestate;
 use crate::physics;
-use bevy::prelude::*;
+use crate::sprite;
+use crate::timer;
+use crate::worldconfig;
 use bounds_deletion::*;
 use gamedata::*;
 use gamestate::*;
+use macroquad::prelude::*;
+use macroquad::rand::gen_range;
 use physics::*;
-use rand::{thread_rng, Rng};
+use sprite::*;
+use timer::*;
+use worldconfig::*;
 
-pub struct Pipe;
+pub struct Pipe {
+    pub sprite: Sprite,
+    pub velocity: Velocity,
+}
+
+// Gives a point when the player flies through it
+pub struct ScoreGiver {
+    pub position: Vec2,
+    pub velocity: Velocity,
+}
 
 pub struct SpawnTimer {
     pub timer: Timer,
@@ -27,128 +42,126 @@ pub struct PipeSpawnSettings {
     pub max_center_delta: f32,
 }
 
-#[derive(PartialEq)]
-pub enum Collider {
-    Solid,
-    ScoreGiver,
+pub struct Pipes {
+    pub pipes: Vec<Pipe>,
+    pub score_givers: Vec<ScoreGiver>,
+    spawn_timer: SpawnTimer,
+    pipe_settings: PipeSpawnSettings,
+    pipe_texture: Texture2D,
 }
 
-pub struct PipePlugin;
-
-impl Plugin for PipePlugin {
-    fn build(&self, app: &mut AppBuilder) {
-        app.add_system(spawn_pipe_system.system())
-            .add_resource(SpawnTimer {
-                timer: Timer::from_seconds(2.0, true),
+impl Pipes {
+    pub fn new(pipe_texture: Texture2D) -> Self {
+        Pipes {
+            pipes: Vec::new(),
+            score_givers: Vec::new(),
+            spawn_timer: SpawnTimer {
+                timer: Timer::from_seconds(2.0),
                 last_pos: 0.5,
-            })
-            .add_resource(PipeSpawnSettings {
+            },
+            pipe_settings: PipeSpawnSettings {
                 min_time: 0.9,
                 max_time: 1.2,
                 speed: -700.0,
                 min_pipe_distance: 300.0,
                 max_pipe_distance: 600.0,
                 max_center_delta: 0.4,
-            });
+            },
+            pipe_texture,
+        }
     }
-}
 
-fn spawn_pipe_system(
-    mut commands: Commands,
-    pipe_settings: Res<PipeSpawnSettings>,
-    game_data: Res<GameData>,
-    asset_server: Res<AssetServer>,
-    time: Res<Time>,
-    mut spawn_timer: ResMut<SpawnTimer>,
-    mut materials: ResMut<Assets<ColorMaterial>>,
-    mut textures: ResMut<Assets<Texture>>,
-) {
-    if game_data.game_state != GameState::Playing {
-        return;
+    pub fn update(&mut self, world_config: &WorldConfig, game_data: &GameData, dt: f32) {
+        self.spawn_pipe_system(world_config, game_data, dt);
+        for pipe in self.pipes.iter_mut() {
+            velocity_system(dt, &mut pipe.sprite.position, &pipe.velocity);
+        }
+        for score_giver in self.score_givers.iter_mut() {
+            velocity_system(dt, &mut score_giver.position, &score_giver.velocity);
+        }
+        self.pipes
+            .retain(|pipe| !is_offscreen(world_config, pipe.sprite.position, pipe.sprite.size()));
+        self.score_givers
+            .retain(|score_giver| !is_offscreen(world_config, score_giver.position, Vec2::ZERO));
     }
 
-    spawn_timer.timer.tick(time.delta_seconds);
-    if !spawn_timer.timer.finished {
-        return;
+    pub fn clear(&mut self) {
+        self.pipes.clear();
+        self.score_givers.clear();
     }
 
-    let mut rng = thread_rng();
-    spawn_timer.timer.duration = rng.gen_range(pipe_settings.min_time, pipe_settings.max_time);
+    pub fn draw(&self) {
+        for pipe in self.pipes.iter() {
+            pipe.sprite.draw();
+        }
+    }
 
-    let mut new_center_pos = spawn_timer.last_pos
-        - rng.gen_range(
-            -pipe_settings.max_center_delta,
-            pipe_settings.max_center_delta,
+    fn spawn_pipe_system(&mut self, world_config: &WorldConfig, game_data: &GameData, dt: f32) {
+        if game_data.game_state != GameState::Playing {
+            return;
+        }
+
+        let spawn_timer = &mut self.spawn_timer;
+        let pipe_settings = &self.pipe_settings;
+        spawn_timer.timer.tick(dt);
+        if !spawn_timer.timer.finished {
+            return;
+        }
+
+        spawn_timer.timer.duration = gen_range(pipe_settings.min_time, pipe_settings.max_time);
+
+        let mut new_center_pos = spawn_timer.last_pos
+            - gen_range(
+                -pipe_settings.max_center_delta,
+                pipe_settings.max_center_delta,
+            );
+
+        let half_play_area = world_config.half_play_area();
+        let pipe_size = world_config.pipe_world_size();
+        // This is the extent from the center in Y, a pipe can go maximum, until it flies in the air
+        let clamp_range = (world_config.play_area.y - pipe_size.y) / world_config.play_area.y;
+
+        new_center_pos = new_center_pos.clamp(-clamp_range, clamp_range);
+        spawn_timer.last_pos = new_center_pos;
+        // to world units
+        new_center_pos *= half_play_area.y;
+
+        let pipe_offset_y = pipe_size.y * 0.5;
+        let pipe_offset_x = pipe_size.x * 0.5;
+        let mut pipe_delta = gen_range(
+            pipe_settings.min_pipe_distance,
+            pipe_settings.max_pipe_distance,
         );
-
-    // sorry for the hardcoded values
-    // This is the extent from the center in Y, a pipe can go maximum, until it flies in the air
-    let clamp_range = (1280.0 - (6.0 * 128.0)) / 1280.0;
-
-    // Clamp func seem to be nightly only for now
-    new_center_pos = new_center_pos.min(clamp_range);
-    new_center_pos = new_center_pos.max(-clamp_range);
-    spawn_timer.last_pos = new_center_pos;
-    // to world units
-    new_center_pos *= 1280.0 * 0.5;
-
-    let pipe_texture_handle = asset_server
-        .load_sync(&mut textures, "assets/pipe.png")
-        .unwrap();
-
-    let pipe_offset_y = (6.0 * 128.0) * 0.5;
-    let pipe_offset_x = (6.0 * 32.0) * 0.5;
-    let mut pipe_delta = rng.gen_range(
-        pipe_settings.min_pipe_distance,
-        pipe_settings.max_pipe_distance,
-    );
-    // half the size because both pipes will be offseted in opposide direction
-    pipe_delta *= 0.5;
-    let x_pos = 1920.0 * 0.5 + pipe_offset_x;
-
-    // lower pipe
-    commands
-        .spawn(SpriteComponents {
-            material: materials.add(pipe_texture_handle.into()),
-            scale: Scale(6.0),
-            draw: Draw {
-                is_transparent: true,
-                is_visible: true,
-                render_commands: Vec::new(),
-            },
-            translation: Translation::new(x_pos, -pipe_offset_y + new_center_pos - pipe_delta, 3.0),
-            ..Default::default()
-        })
-        .with(Velocity(Vec2::new(pipe_settings.speed, 0.0)))
-        .with(Pipe)
-        .with(OffsceenDeletion)
-        .with(Collider::Solid);
-    // higher pipe
-    commands
-        .spawn(SpriteComponents {
-            material: materials.add(pipe_texture_handle.into()),
-            scale: Scale(6.0),
-            draw: Draw {
-                is_transparent: true,
-                is_visible: true,
-                render_commands: Vec::new(),
-            },
-            translation: Translation::new(x_pos, pipe_offset_y + new_center_pos + pipe_delta, 3.0),
-            rotation: Rotation::from_rotation_z(std::f32::consts::PI),
-            ..Default::default()
-        })
-        .with(Pipe)
-        .with(OffsceenDeletion)
-        .with(Velocity(Vec2::new(pipe_settings.speed, 0.0)))
-        .with(Collider::Solid);
-
-    // score collider offseted by half player size
-    let score_offset = Vec3::new(32.0 * 6.0 * 0.5, 0.0, 0.0);
-    commands
-        .spawn((
-            Translation(score_offset + Vec3::new(x_pos, 0.0, 0.0)),
-            Collider::ScoreGiver,
-            Velocity(Vec2::new(pipe_settings.speed, 0.0)),
-        ))
-        .with(OffsceenDeletion);
+        // half the size because both pipes will be offseted in opposide direction
+        pipe_delta *= 0.5;
+        let x_pos = half_play_area.x + pipe_offset_x;
+
+        // lower pipe
+        self.pipes.push(Pipe {
+            sprite: Sprite::new(
+                &self.pipe_texture,
+                vec2(x_pos, -pipe_offset_y + new_center_pos - pipe_delta),
+                world_config.sprite_scale,
+            ),
+            velocity: Velocity(vec2(pipe_settings.speed, 0.0)),
+        });
+        // higher pipe
+        let mut higher_pipe = Sprite::new(
+            &self.pipe_texture,
+            vec2(x_pos, pipe_offset_y + new_center_pos + pipe_delta),
+            world_config.sprite_scale,
+        );
+        higher_pipe.rotation = std::f32::consts::PI;
+        self.pipes.push(Pipe {
+            sprite: higher_pipe,
+            velocity: Velocity(vec2(pipe_settings.speed, 0.0)),
+        });
+
+        // score collider offseted by half player size
+        let score_offset = vec2(world_config.bird_world_size().x * 0.5, 0.0);
+        self.score_givers.push(ScoreGiver {
+            position: score_offset + vec2(x_pos, 0.0),
+            velocity: Velocity(vec2(pipe_settings.speed, 0.0)),
+        });
+    }
 }
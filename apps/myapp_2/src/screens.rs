@@ -1,38 +1,26 @@
-use bevy::prelude::*;
+use crate::sprite;
+use macroquad::prelude::*;
+use sprite::*;
 
-pub struct StartScreen;
-pub struct EndScreen;
-
-pub struct ScreensPlugin;
+pub struct Screens {
+    pub start_screen: Sprite,
+    pub end_screen: Sprite,
+}
 
-impl Plugin for ScreensPlugin {
-    fn build(&self, app: &mut AppBuilder) {
-        app.add_startup_system(setup.system());
+impl Screens {
+    pub async fn load() -> Self {
+        let start_texture = load_sprite_texture("assets/SpaceToStart.png").await;
+        let game_over_texture = load_sprite_texture("assets/GameOverText.png").await;
+        let mut end_screen = Sprite::new(&game_over_texture, Vec2::ZERO, 1.0);
+        end_screen.is_visible = false;
+        Screens {
+            start_screen: Sprite::new(&start_texture, Vec2::ZERO, 1.0),
+            end_screen,
+        }
     }
-}
 
-fn setup(
-    mut commands: Commands,
-    asset_server: Res<AssetServer>,
-    mut materials: ResMut<Assets<ColorMaterial>>,
-) {
-    let start_texture_handle = asset_server.load("assets/SpaceToStart.png").unwrap();
-    let game_over_texture_handle = asset_server.load("assets/GameOverText.png").unwrap();
-    commands
-        // Start Screen
-        .spawn(SpriteComponents {
-            material: materials.add(start_texture_handle.into()),
-            ..Default::default()
-        })
-        .with(StartScreen)
-        .spawn(SpriteComponents {
-            material: materials.add(game_over_texture_handle.into()),
-            draw: Draw {
-                is_transparent: true,
-                is_visible: false,
-                render_commands: Vec::new(),
-            },
-            ..Default::default()
-        })
-        .with(EndScreen);
+    pub fn draw(&self) {
+        self.start_screen.draw();
+        self.end_screen.draw();
+    }
 }
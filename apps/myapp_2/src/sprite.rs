@@ -0,0 +1,75 @@
+use macroquad::prelude::*;
+
+// A texture drawn centered on its position, the way bevy draws sprites.
+// The world's y axis points up, see the camera in main.rs
+pub struct Sprite {
+    pub texture: Texture2D,
+    pub position: Vec2,
+    pub scale: f32,
+    // Counterclockwise, in radians
+    pub rotation: f32,
+    pub color: Color,
+    pub is_visible: bool,
+    // Part of the texture to draw, all of it when None
+    pub source: Option<Rect>,
+}
+
+impl Sprite {
+    pub fn new(texture: &Texture2D, position: Vec2, scale: f32) -> Self {
+        Sprite {
+            texture: texture.clone(),
+            position,
+            scale,
+            rotation: 0.0,
+            color: WHITE,
+            is_visible: true,
+            source: None,
+        }
+    }
+
+    // Show cell `index` of a sheet cut into a `columns` by `rows` grid, like a bevy TextureAtlas
+    pub fn set_atlas_index(&mut self, columns: u32, rows: u32, index: u32) {
+        let cell = self.texture.size() / vec2(columns as f32, rows as f32);
+        let x = (index % columns) as f32 * cell.x;
+        let y = (index / columns) as f32 * cell.y;
+        self.source = Some(Rect::new(x, y, cell.x, cell.y));
+    }
+
+    // Size in world units
+    pub fn size(&self) -> Vec2 {
+        match self.source {
+            Some(source) => source.size() * self.scale,
+            None => self.texture.size() * self.scale,
+        }
+    }
+
+    pub fn draw(&self) {
+        if !self.is_visible {
+            return;
+        }
+        let size = self.size();
+        draw_texture_ex(
+            &self.texture,
+            self.position.x - size.x * 0.5,
+            self.position.y - size.y * 0.5,
+            self.color,
+            DrawTextureParams {
+                dest_size: Some(size),
+                source: self.source,
+                rotation: self.rotation,
+                // Textures are stored top row first and would land at the
+                // bottom of the y up world camera. The rotation turns
+                // counterclockwise on screen, so a flap tilts the nose up
+                flip_y: true,
+                ..Default::default()
+            },
+        );
+    }
+}
+
+pub async fn load_sprite_texture(path: &str) -> Texture2D {
+    let texture = load_texture(path).await.unwrap();
+    // Pixel art, keep it crisp when scaled up
+    texture.set_filter(FilterMode::Nearest);
+    texture
+}
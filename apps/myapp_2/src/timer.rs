@@ -0,0 +1,25 @@
+// Stand-in for bevy's Timer, ticked by hand with the frame time
+pub struct Timer {
+    pub duration: f32,
+    pub elapsed: f32,
+    // True for the one tick it ran out on, then it starts over
+    pub finished: bool,
+}
+
+impl Timer {
+    pub fn from_seconds(seconds: f32) -> Self {
+        Timer {
+            duration: seconds,
+            elapsed: 0.0,
+            finished: false,
+        }
+    }
+
+    pub fn tick(&mut self, delta: f32) {
+        self.elapsed += delta;
+        self.finished = self.elapsed >= self.duration;
+        if self.finished {
+            self.elapsed = 0.0;
+        }
+    }
+}
@@ -0,0 +1,43 @@
+use macroquad::prelude::*;
+
+// Dimensions everything is laid out with, in world units unless noted otherwise
+pub struct WorldConfig {
+    // Visible part of the world, centered on the origin
+    pub play_area: Vec2,
+    // The pixel art is drawn this many times its size
+    pub sprite_scale: f32,
+    // In pixels, one cell of the bird's sprite sheet
+    pub bird_size: Vec2,
+    // In pixels
+    pub pipe_size: Vec2,
+}
+
+impl WorldConfig {
+    // The sprite sizes come from the loaded art, so the sprites, the hitboxes and
+    // the layout always agree
+    pub fn new(bird_size: Vec2, pipe_size: Vec2) -> Self {
+        WorldConfig {
+            play_area: vec2(1920.0, 1280.0),
+            sprite_scale: 6.0,
+            bird_size,
+            pipe_size,
+        }
+    }
+
+    pub fn half_play_area(&self) -> Vec2 {
+        self.play_area * 0.5
+    }
+
+    pub fn bird_world_size(&self) -> Vec2 {
+        self.bird_size * self.sprite_scale
+    }
+
+    pub fn pipe_world_size(&self) -> Vec2 {
+        self.pipe_size * self.sprite_scale
+    }
+
+    // Keeps the height and stretches the width to the window's aspect
+    pub fn fit_window(&mut self) {
+        self.play_area.x = self.play_area.y * screen_width() / screen_height();
+    }
+}
@@ -5,11 +5,14 @@ APP_NAME=`\
     grep "^name =" ${APP_DIRECTORY}/Cargo.toml |\
     grep -o "\".*\"" |tr -d "\"" || exit 1`
 HTML_DIRECTORY=/html
+LIBS_DIRECTORY=${LIBS_DIRECTORY:-/libs}
 PORT=8080
 
 if [ "$1" = "serve" ];then
     cd "${HTML_DIRECTORY}" || exit 1
     cp -r "${APP_DIRECTORY}"/public/* . || exit 1
+    # browser side of the shared libraries, e.g. libs/storage/public/storage.js
+    cp -r "${LIBS_DIRECTORY}"/*/public/* . 2>/dev/null
     basic-http-server -a 0.0.0.0:${PORT} .
 elif [ "$1" = "update" ];then
     HASH=$(cat /dev/urandom | tr -dc 'a-zA-Z0-9' | fold -w 10 | head -n 1)
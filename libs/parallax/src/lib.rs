@@ -0,0 +1,140 @@
+//! Parallax scrolling backgrounds for macroquad games.
+//!
+//! A background is a list of [`LayerConfig`]s, back to front. Each layer is a
+//! tile that repeats horizontally and moves at its own share of the camera
+//! speed, so far layers crawl and near ones keep up with the action.
+//!
+//! ```ignore
+//! const LAYERS: &[LayerConfig] = &[
+//!     LayerConfig {
+//!         source: Source::Texture("sky.png"),
+//!         scroll: 0.1,
+//!         y: 0.0,
+//!         height: 600.0,
+//!         tile_width: 800.0,
+//!         mirror: true,
+//!     },
+//! ];
+//! let background = Parallax::load(LAYERS).await.unwrap();
+//! // every frame, with `camera_x` the distance the world scrolled so far
+//! background.draw(camera_x, screen_width());
+//! ```
+
+use macroquad::prelude::*;
+
+/// What a layer's tile looks like.
+pub enum Source {
+    /// An image file, stretched to the tile size.
+    Texture(&'static str),
+    /// A generated silhouette of rolling hills, filled below the outline.
+    Hills {
+        /// Fill color of the hills.
+        color: Color,
+        /// How high the outline rises from the bottom before the waves are
+        /// added, as a share of the layer height.
+        base: f32,
+        /// A sine wave added to the outline for every `(periods, amplitude,
+        /// phase)`. Whole periods per tile keep the tiling seamless, the amplitude
+        /// is in pixels and the phase (in radians) shifts a wave sideways so the
+        /// peaks of different waves don't all line up at the tile's edge.
+        waves: &'static [(u32, f32, f32)],
+    },
+}
+
+pub struct LayerConfig {
+    /// What the tile looks like.
+    pub source: Source,
+    /// Share of the camera movement this layer follows, 0 stands still, 1 moves with the world.
+    pub scroll: f32,
+    /// Top edge of the layer on screen.
+    pub y: f32,
+    /// Height of the layer on screen, the tile is stretched to it.
+    pub height: f32,
+    /// Width of one tile on screen, the tile repeats every this many pixels.
+    pub tile_width: f32,
+    /// Flip every other tile so images that don't tile still meet without a seam.
+    pub mirror: bool,
+}
+
+struct Layer {
+    texture: Texture2D,
+    scroll: f32,
+    y: f32,
+    height: f32,
+    tile_width: f32,
+    mirror: bool,
+}
+
+pub struct Parallax {
+    layers: Vec<Layer>,
+}
+
+fn hills_image(width: u16, height: u16, color: Color, base: f32, waves: &[(u32, f32, f32)]) -> Image {
+    let mut image = Image::gen_image_color(width, height, BLANK);
+    for x in 0..width as u32 {
+        let t = x as f32 / width as f32 * std::f32::consts::TAU;
+        let rise = base * height as f32
+            + waves
+                .iter()
+                .map(|&(periods, amplitude, phase)| amplitude * (t * periods as f32 + phase).sin())
+                .sum::<f32>();
+        let top = (height as f32 - rise).clamp(0.0, height as f32) as u32;
+        for y in top..height as u32 {
+            image.set_pixel(x, y, color);
+        }
+    }
+    image
+}
+
+impl Parallax {
+    /// Loads or generates every layer's tile.
+    pub async fn load(configs: &[LayerConfig]) -> Result<Parallax, macroquad::Error> {
+        let mut layers = Vec::with_capacity(configs.len());
+        for config in configs {
+            let texture = match &config.source {
+                Source::Texture(path) => load_texture(path).await?,
+                Source::Hills { color, base, waves } => Texture2D::from_image(&hills_image(
+                    config.tile_width as u16,
+                    config.height as u16,
+                    *color,
+                    *base,
+                    waves,
+                )),
+            };
+            layers.push(Layer {
+                texture,
+                scroll: config.scroll,
+                y: config.y,
+                height: config.height,
+                tile_width: config.tile_width,
+                mirror: config.mirror,
+            });
+        }
+        Ok(Parallax { layers })
+    }
+
+    /// Draws all layers back to front, covering `view_width` from x = 0.
+    pub fn draw(&self, camera_x: f32, view_width: f32) {
+        for layer in &self.layers {
+            let shift = camera_x * layer.scroll;
+            let first = (shift / layer.tile_width).floor() as i64;
+            let mut x = first as f32 * layer.tile_width - shift;
+            let mut tile = first;
+            while x < view_width {
+                draw_texture_ex(
+                    &layer.texture,
+                    x,
+                    layer.y,
+                    WHITE,
+                    DrawTextureParams {
+                        dest_size: Some(vec2(layer.tile_width, layer.height)),
+                        flip_x: layer.mirror && tile.rem_euclid(2) == 1,
+                        ..Default::default()
+                    },
+                );
+                x += layer.tile_width;
+                tile += 1;
+            }
+        }
+    }
+}
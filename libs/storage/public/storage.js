@@ -0,0 +1,18 @@
+// Exposes localStorage to the wasm module, see src/lib.rs
+miniquad_add_plugin({
+    name: "storage",
+    version: "0.1.0",
+    register_plugin: function (importObject) {
+        importObject.env.storage_set = function (key, key_len, value, value_len) {
+            localStorage.setItem(UTF8ToString(key, key_len), UTF8ToString(value, value_len));
+        };
+        importObject.env.storage_len = function (key, key_len) {
+            var value = localStorage.getItem(UTF8ToString(key, key_len));
+            return value === null ? -1 : toUTF8Array(value).length;
+        };
+        importObject.env.storage_get = function (key, key_len, buf, buf_len) {
+            var bytes = toUTF8Array(localStorage.getItem(UTF8ToString(key, key_len)) || "");
+            new Uint8Array(wasm_memory.buffer, buf, buf_len).set(bytes.slice(0, buf_len));
+        };
+    }
+});
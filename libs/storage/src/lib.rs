@@ -0,0 +1,81 @@
+//! Small key/value store that survives restarts: `localStorage` in the browser,
+//! a text file per key on native.
+//!
+//! The browser side is a miniquad plugin in public/storage.js. files/watchme.sh
+//! copies it next to every app's index.html, which loads it right after
+//! mq_js_bundle.js.
+
+#[cfg(target_arch = "wasm32")]
+mod backend {
+    extern "C" {
+        fn storage_set(key: *const u8, key_len: usize, value: *const u8, value_len: usize);
+        fn storage_len(key: *const u8, key_len: usize) -> i32;
+        fn storage_get(key: *const u8, key_len: usize, buf: *mut u8, buf_len: usize);
+    }
+
+    // Checked by mq_js_bundle.js against the plugin's version, 0.1.0
+    #[no_mangle]
+    pub extern "C" fn storage_crate_version() -> u32 {
+        1 << 16
+    }
+
+    pub fn load(key: &str) -> Option<String> {
+        let len = unsafe { storage_len(key.as_ptr(), key.len()) };
+        if len < 0 {
+            return None;
+        }
+        let mut buf = vec![0u8; len as usize];
+        unsafe { storage_get(key.as_ptr(), key.len(), buf.as_mut_ptr(), buf.len()) };
+        String::from_utf8(buf).ok()
+    }
+
+    pub fn save(key: &str, value: &str) {
+        unsafe { storage_set(key.as_ptr(), key.len(), value.as_ptr(), value.len()) };
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+mod backend {
+    fn path(key: &str) -> String {
+        format!("{}.txt", key)
+    }
+
+    pub fn load(key: &str) -> Option<String> {
+        std::fs::read_to_string(path(key)).ok()
+    }
+
+    pub fn save(key: &str, value: &str) {
+        if let Err(err) = std::fs::write(path(key), value) {
+            eprintln!("could not save {}: {}", key, err);
+        }
+    }
+}
+
+pub use backend::{load, save};
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Native keys are files in the working directory, each test uses its own
+    fn cleanup(key: &str) {
+        let _ = std::fs::remove_file(format!("{}.txt", key));
+    }
+
+    #[test]
+    fn saved_value_loads_back() {
+        let key = "storage_test_round_trip";
+        save(key, "first line\nsecond line");
+        assert_eq!(load(key).as_deref(), Some("first line\nsecond line"));
+        save(key, "overwritten");
+        assert_eq!(load(key).as_deref(), Some("overwritten"));
+        cleanup(key);
+    }
+
+    #[test]
+    fn missing_key_loads_none() {
+        let key = "storage_test_missing";
+        cleanup(key);
+        assert_eq!(load(key), None);
+    }
+}